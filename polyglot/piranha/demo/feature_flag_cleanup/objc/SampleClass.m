@@ -0,0 +1,59 @@
+/**
+ *    Copyright (c) 2022 Uber Technologies, Inc.
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+#import <Foundation/Foundation.h>
+
+@implementation UBEExperimentExamples
+
+- (void)ifStatements {
+  if ([self.experiments
+          isTreatedForExperiment:UBExperimentNameSomething]) {
+    NSLog(@"treated");
+  } else {
+    NSLog(@"control");
+  }
+
+  if ([self.experiments isInControlGroupForExperiment:UBExperimentNameSomething] || self.enabled) {
+    NSLog(@"control or enabled");
+  }
+
+  if (![self.experiments isTreatedForExperiment:UBExperimentNameSomething]) {
+    NSLog(@"control");
+  }
+}
+
+- (NSString *)ternary {
+  return [self.experiments isTreatedForExperiment:UBExperimentNameSomething] ? @"treated" : @"control";
+}
+
+- (void)localVariables {
+  BOOL isTreated = [self.experiments isTreatedForExperiment:UBExperimentNameSomething];
+  if (isTreated && self.enabled) {
+    NSLog(@"treated and enabled");
+  }
+  [self configure:isTreated];
+}
+
+- (NSString *)earlyReturn {
+  [self.experiments sendInclusionEventForExperiment:UBExperimentNameSomething];
+  if ([self.experiments isTreatedForExperiment:UBExperimentNameSomething]) {
+    return @"treated";
+  }
+  NSLog(@"control");
+  return @"control";
+}
+
+@end
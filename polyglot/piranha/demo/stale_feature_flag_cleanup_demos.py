@@ -22,10 +22,28 @@ def run_kt_ff_demo():
     for summary in output_summary_kt:
         assert len(summary.rewrites) > 0
 
+def run_js_ff_demo():
+    info("Running the stale feature flag cleanup demo for JavaScript")
+    output_summary_js = run_piranha_cli(join(feature_flag_dir, "js"), join(feature_flag_dir, 'js/configurations'), True)
+    assert len(output_summary_js) == 1
+
+    for summary in output_summary_js:
+        assert len(summary.rewrites) > 0
+
+def run_objc_ff_demo():
+    info("Running the stale feature flag cleanup demo for Objective-C")
+    output_summary_objc = run_piranha_cli(join(feature_flag_dir, "objc"), join(feature_flag_dir, 'objc/configurations'), True)
+    assert len(output_summary_objc) == 1
+
+    for summary in output_summary_objc:
+        assert len(summary.rewrites) > 0
+
 FORMAT = '%(levelname)s %(name)s %(asctime)-15s %(filename)s:%(lineno)d %(message)s'
 logging.basicConfig(format=FORMAT)
 logging.getLogger().setLevel(logging.INFO)
 
 run_java_ff_demo()
 run_kt_ff_demo()
+run_js_ff_demo()
+run_objc_ff_demo()
 print("Completed running the stale feature flag cleanup demos")
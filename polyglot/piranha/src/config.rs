@@ -19,6 +19,7 @@ use crate::{
   models::piranha_arguments::PiranhaArguments,
   models::{
     outgoing_edges::{Edges, OutgoingEdges},
+    piranha_error::PiranhaError,
     rule::{Rule, Rules},
     scopes::{ScopeConfig, ScopeGenerator},
   },
@@ -42,50 +43,131 @@ pub(crate) struct CommandLineArguments {
   /// Path to output summary json
   #[clap(short = 'j', long)]
   pub(crate) path_to_output_summary: Option<String>,
+  /// Glob pattern (relative to the code base) of the files to process. Can be repeated (in addition to the `include` in `piranha_arguments.toml`)
+  #[clap(long)]
+  pub(crate) include: Vec<String>,
+  /// Glob pattern (relative to the code base) of the files to never process. Can be repeated (in addition to the `exclude` in `piranha_arguments.toml`)
+  #[clap(long)]
+  pub(crate) exclude: Vec<String>,
 }
 
-fn read_language_specific_rules(language_name: &str) -> Rules {
+fn read_language_specific_rules(language_name: &str) -> Result<Rules, PiranhaError> {
   match language_name {
     "java" => parse_toml(include_str!("cleanup_rules/java/rules.toml")),
     "kt" => parse_toml(include_str!("cleanup_rules/kt/rules.toml")),
-    "swift" => parse_toml(include_str!("cleanup_rules/kt/rules.toml")),
-    _ => Rules::default(),
+    "swift" => parse_toml(include_str!("cleanup_rules/swift/rules.toml")),
+    "go" => parse_toml(include_str!("cleanup_rules/go/rules.toml")),
+    "ts" => parse_toml(include_str!("cleanup_rules/ts/rules.toml")),
+    "js" => parse_toml(include_str!("cleanup_rules/js/rules.toml")),
+    "py" => parse_toml(include_str!("cleanup_rules/py/rules.toml")),
+    "cs" => parse_toml(include_str!("cleanup_rules/cs/rules.toml")),
+    "objc" => parse_toml(include_str!("cleanup_rules/objc/rules.toml")),
+    "rb" => parse_toml(include_str!("cleanup_rules/rb/rules.toml")),
+    "scala" => parse_toml(include_str!("cleanup_rules/scala/rules.toml")),
+    "rs" => parse_toml(include_str!("cleanup_rules/rs/rules.toml")),
+    "php" => parse_toml(include_str!("cleanup_rules/php/rules.toml")),
+    "dart" => parse_toml(include_str!("cleanup_rules/dart/rules.toml")),
+    "yaml" => parse_toml(include_str!("cleanup_rules/yaml/rules.toml")),
+    "json" => parse_toml(include_str!("cleanup_rules/json/rules.toml")),
+    "toml" => parse_toml(include_str!("cleanup_rules/toml/rules.toml")),
+    "xml" => parse_toml(include_str!("cleanup_rules/xml/rules.toml")),
+    "c" => parse_toml(include_str!("cleanup_rules/c/rules.toml")),
+    // The C++ specific rules complement the C rules (and replace the C rules with the same name)
+    "cpp" => {
+      let cpp_rules = parse_toml::<Rules>(include_str!("cleanup_rules/cpp/rules.toml"))?.rules;
+      let c_rules: Vec<Rule> = parse_toml::<Rules>(include_str!("cleanup_rules/c/rules.toml"))?
+        .rules
+        .into_iter()
+        .filter(|r| {
+          !cpp_rules
+            .iter()
+            .any(|cpp_rule| cpp_rule.name().eq(&r.name()))
+        })
+        .collect();
+      Ok(Rules {
+        rules: [c_rules, cpp_rules].concat(),
+      })
+    }
+    // The TSX specific (i.e. JSX) rules complement the TypeScript rules
+    "tsx" => Ok(Rules {
+      rules: [
+        parse_toml::<Rules>(include_str!("cleanup_rules/ts/rules.toml"))?.rules,
+        parse_toml::<Rules>(include_str!("cleanup_rules/tsx/rules.toml"))?.rules,
+      ]
+      .concat(),
+    }),
+    _ => Ok(Rules::default()),
   }
 }
 
-fn read_language_specific_edges(language_name: &str) -> Edges {
+fn read_language_specific_edges(language_name: &str) -> Result<Edges, PiranhaError> {
   match language_name {
     "java" => parse_toml(include_str!("cleanup_rules/java/edges.toml")),
     "kt" => parse_toml(include_str!("cleanup_rules/kt/edges.toml")),
-    _ => Edges::default(),
+    "swift" => parse_toml(include_str!("cleanup_rules/swift/edges.toml")),
+    "go" => parse_toml(include_str!("cleanup_rules/go/edges.toml")),
+    "ts" => parse_toml(include_str!("cleanup_rules/ts/edges.toml")),
+    "js" => parse_toml(include_str!("cleanup_rules/js/edges.toml")),
+    "py" => parse_toml(include_str!("cleanup_rules/py/edges.toml")),
+    "cs" => parse_toml(include_str!("cleanup_rules/cs/edges.toml")),
+    "objc" => parse_toml(include_str!("cleanup_rules/objc/edges.toml")),
+    "rb" => parse_toml(include_str!("cleanup_rules/rb/edges.toml")),
+    "scala" => parse_toml(include_str!("cleanup_rules/scala/edges.toml")),
+    "rs" => parse_toml(include_str!("cleanup_rules/rs/edges.toml")),
+    "php" => parse_toml(include_str!("cleanup_rules/php/edges.toml")),
+    "dart" => parse_toml(include_str!("cleanup_rules/dart/edges.toml")),
+    "yaml" => parse_toml(include_str!("cleanup_rules/yaml/edges.toml")),
+    "json" => parse_toml(include_str!("cleanup_rules/json/edges.toml")),
+    "toml" => parse_toml(include_str!("cleanup_rules/toml/edges.toml")),
+    "xml" => parse_toml(include_str!("cleanup_rules/xml/edges.toml")),
+    "c" | "cpp" => parse_toml(include_str!("cleanup_rules/c/edges.toml")),
+    "tsx" => Ok(Edges {
+      edges: [
+        parse_toml::<Edges>(include_str!("cleanup_rules/ts/edges.toml"))?.edges,
+        parse_toml::<Edges>(include_str!("cleanup_rules/tsx/edges.toml"))?.edges,
+      ]
+      .concat(),
+    }),
+    _ => Ok(Edges::default()),
   }
 }
 
-fn read_scope_config(language_name: &str) -> Vec<ScopeGenerator> {
-  match language_name {
-    "java" => {
-      parse_toml::<ScopeConfig>(include_str!("cleanup_rules/java/scope_config.toml")).scopes()
-    }
-    "kt" => parse_toml::<ScopeConfig>(include_str!("cleanup_rules/kt/scope_config.toml")).scopes(),
-    "swift" => {
-      parse_toml::<ScopeConfig>(include_str!("cleanup_rules/swift/scope_config.toml")).scopes()
-    }
-    _ => Vec::new(),
-  }
+fn read_scope_config(language_name: &str) -> Result<Vec<ScopeGenerator>, PiranhaError> {
+  let scope_config: ScopeConfig = match language_name {
+    "java" => parse_toml(include_str!("cleanup_rules/java/scope_config.toml"))?,
+    "kt" => parse_toml(include_str!("cleanup_rules/kt/scope_config.toml"))?,
+    "swift" => parse_toml(include_str!("cleanup_rules/swift/scope_config.toml"))?,
+    "go" => parse_toml(include_str!("cleanup_rules/go/scope_config.toml"))?,
+    "ts" | "tsx" => parse_toml(include_str!("cleanup_rules/ts/scope_config.toml"))?,
+    "js" => parse_toml(include_str!("cleanup_rules/js/scope_config.toml"))?,
+    "py" => parse_toml(include_str!("cleanup_rules/py/scope_config.toml"))?,
+    "cs" => parse_toml(include_str!("cleanup_rules/cs/scope_config.toml"))?,
+    "objc" => parse_toml(include_str!("cleanup_rules/objc/scope_config.toml"))?,
+    "rb" => parse_toml(include_str!("cleanup_rules/rb/scope_config.toml"))?,
+    "scala" => parse_toml(include_str!("cleanup_rules/scala/scope_config.toml"))?,
+    "c" => parse_toml(include_str!("cleanup_rules/c/scope_config.toml"))?,
+    "cpp" => parse_toml(include_str!("cleanup_rules/cpp/scope_config.toml"))?,
+    "rs" => parse_toml(include_str!("cleanup_rules/rs/scope_config.toml"))?,
+    "php" => parse_toml(include_str!("cleanup_rules/php/scope_config.toml"))?,
+    "dart" => parse_toml(include_str!("cleanup_rules/dart/scope_config.toml"))?,
+    _ => ScopeConfig::default(),
+  };
+  Ok(scope_config.scopes())
 }
 
-pub(crate) fn read_config_files(
-  args: &PiranhaArguments,
-) -> (Vec<Rule>, Vec<OutgoingEdges>, Vec<ScopeGenerator>) {
+/// The rules, edges and scopes read from the language specific and the API specific configurations.
+type Configurations = (Vec<Rule>, Vec<OutgoingEdges>, Vec<ScopeGenerator>);
+
+pub(crate) fn read_config_files(args: &PiranhaArguments) -> Result<Configurations, PiranhaError> {
   let path_to_config = Path::new(args.path_to_configurations());
   // Read the language specific cleanup rules and edges
-  let language_rules: Rules = read_language_specific_rules(args.language_name());
-  let language_edges: Edges = read_language_specific_edges(args.language_name());
-  let scopes = read_scope_config(args.language_name());
+  let language_rules: Rules = read_language_specific_rules(args.language_name())?;
+  let language_edges: Edges = read_language_specific_edges(args.language_name())?;
+  let scopes = read_scope_config(args.language_name())?;
 
   // Read the API specific cleanup rules and edges
-  let mut input_rules: Rules = read_toml(&path_to_config.join("rules.toml"), true);
-  let input_edges: Edges = read_toml(&path_to_config.join("edges.toml"), true);
+  let mut input_rules: Rules = read_toml(&path_to_config.join("rules.toml"), true)?;
+  let input_edges: Edges = read_toml(&path_to_config.join("edges.toml"), true)?;
 
   for r in input_rules.rules.iter_mut() {
     r.add_to_seed_rules_group();
@@ -94,5 +176,5 @@ pub(crate) fn read_config_files(
   let all_rules = [language_rules.rules, input_rules.rules].concat();
   let all_edges = [language_edges.edges, input_edges.edges].concat();
 
-  (all_rules, all_edges, scopes)
+  Ok((all_rules, all_edges, scopes))
 }
@@ -13,8 +13,8 @@ Copyright (c) 2022 Uber Technologies, Inc.
 
 use config::CommandLineArguments;
 use models::{
-  piranha_arguments::PiranhaArguments, piranha_output::PiranhaOutputSummary,
-  source_code_unit::SourceCodeUnit,
+  piranha_arguments::PiranhaArguments, piranha_error::PiranhaError,
+  piranha_output::PiranhaOutputSummary, source_code_unit::SourceCodeUnit,
 };
 
 mod config;
@@ -23,24 +23,37 @@ pub mod models;
 mod tests;
 pub mod utilities;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::PathBuf,
+  time::{Duration, Instant},
+};
 
 use colored::Colorize;
+use ignore::WalkBuilder;
 use itertools::Itertools;
-use jwalk::WalkDir;
-use log::{debug, info};
-use regex::Regex;
+use log::{debug, info, warn};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use tree_sitter::{Parser, Range};
 
 use crate::{
   models::rule_store::RuleStore,
-  utilities::{read_file, tree_sitter_utilities::get_replace_range},
+  utilities::{
+    get_glob_set, read_file,
+    tree_sitter_utilities::{get_parser, get_replace_range},
+  },
 };
 
 use crate::models::scopes::ScopeGenerator;
-use crate::utilities::tree_sitter_utilities::PiranhaHelpers;
+use crate::utilities::tree_sitter_utilities::{PiranhaHelpers, TreeSitterHelpers};
 use crate::{
   models::{
+    combined_query::{CombinedMatches, CombinedQuery},
+    diagnostic::Diagnostic,
+    edit::Edit,
+    grep_heuristics::GrepHeuristics,
+    matches::Match,
     rule::Rule,
     rule_store::{GLOBAL, PARENT},
   },
@@ -60,16 +73,21 @@ use tree_sitter::Node;
 ///
 /// Returns Piranha Output Summary for each file touched or analyzed by Piranha.
 /// For each file, it reports its content after the rewrite, the list of matches and the list of rewrites.
+/// Raises a Python exception if the configuration (e.g. a rule) is invalid.
 #[pyfunction]
 pub fn run_piranha_cli(
-  path_to_codebase: String, path_to_configurations: String, should_rewrite_files: bool,
-) -> Vec<PiranhaOutputSummary> {
+  py: Python<'_>, path_to_codebase: String, path_to_configurations: String,
+  should_rewrite_files: bool,
+) -> PyResult<Vec<PiranhaOutputSummary>> {
   let configuration = PiranhaArguments::new(CommandLineArguments {
     path_to_codebase,
     path_to_configurations,
     path_to_output_summary: None,
-  });
-  execute_piranha(&configuration, should_rewrite_files)
+    include: vec![],
+    exclude: vec![],
+  })?;
+  // Release the GIL, so that the threads processing the files can log (via `pyo3_log`).
+  Ok(py.allow_threads(|| execute_piranha(&configuration, should_rewrite_files))?)
 }
 
 #[pymodule]
@@ -79,13 +97,15 @@ fn polyglot_piranha(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
   Ok(())
 }
 
+/// Executes piranha for the given `configuration`.
+/// Returns an error if the configuration (i.e. the rules, edges or scopes) is invalid.
 pub fn execute_piranha(
   configuration: &PiranhaArguments, should_rewrite_files: bool,
-) -> Vec<PiranhaOutputSummary> {
+) -> Result<Vec<PiranhaOutputSummary>, PiranhaError> {
   info!("Executing Polyglot Piranha !!!");
 
-  let mut flag_cleaner = FlagCleaner::new(configuration);
-  flag_cleaner.perform_cleanup();
+  let mut flag_cleaner = FlagCleaner::new(configuration)?;
+  flag_cleaner.perform_cleanup()?;
 
   let source_code_units = flag_cleaner.get_updated_files();
 
@@ -95,44 +115,64 @@ pub fn execute_piranha(
     }
   }
 
-  // flag_cleaner.relevant_files
-  let summaries = flag_cleaner
-    .get_updated_files()
-    .iter()
-    .map(PiranhaOutputSummary::new)
-    .collect_vec();
+  let summaries = flag_cleaner.get_summaries();
   log_piranha_output_summaries(&summaries);
-  return summaries;
+  Ok(summaries)
 }
 
 fn log_piranha_output_summaries(summaries: &Vec<PiranhaOutputSummary>) {
-    let mut total_number_of_matches : usize = 0;
-    let mut total_number_of_rewrites : usize = 0;
-    for summary in summaries {
-      let number_of_rewrites =&summary.rewrites().len();
-      let number_of_matches = &summary.matches().len();
-      info!("File : {:?}", &summary.path());
-      info!("  # Rewrites : {}", number_of_rewrites);
-      info!("  # Matches : {}", number_of_matches);
-      total_number_of_rewrites += number_of_rewrites;
-      total_number_of_matches += number_of_matches;
+  let mut total_number_of_matches: usize = 0;
+  let mut total_number_of_rewrites: usize = 0;
+  let mut total_number_of_diagnostics: usize = 0;
+  let mut total_number_of_skipped_files: usize = 0;
+  for summary in summaries {
+    info!("File : {:?}", &summary.path());
+    if let Some(reason) = summary.skipped() {
+      info!("  {}", reason);
+      total_number_of_skipped_files += 1;
     }
-    info!("Total files affected/matched {}", &summaries.len());
-    info!("Total number of matches {}", total_number_of_matches);
-    info!("Total number of rewrites {}", total_number_of_rewrites);
+    let number_of_rewrites = &summary.rewrites().len();
+    let number_of_matches = &summary.matches().len();
+    let number_of_diagnostics = &summary.diagnostics().len();
+    info!("  # Rewrites : {}", number_of_rewrites);
+    info!("  # Matches : {}", number_of_matches);
+    info!("  # Rolled back rules : {}", number_of_diagnostics);
+    total_number_of_rewrites += number_of_rewrites;
+    total_number_of_matches += number_of_matches;
+    total_number_of_diagnostics += number_of_diagnostics;
+  }
+  info!("Total files affected/matched {}", &summaries.len());
+  info!("Total number of matches {}", total_number_of_matches);
+  info!("Total number of rewrites {}", total_number_of_rewrites);
+  info!(
+    "Total number of rolled back rules {}",
+    total_number_of_diagnostics
+  );
+  info!(
+    "Total number of skipped files {}",
+    total_number_of_skipped_files
+  );
 }
 
 impl SourceCodeUnit {
   /// Will apply the `rule` to all of its occurrences in the source code unit.
+  /// Returns an error if some edit (performed by `rule` or the cleanup rules it triggers)
+  /// produces syntactically incorrect code.
   fn apply_rule(
-    &mut self, rule: Rule, rules_store: &mut RuleStore, parser: &mut Parser,
+    &mut self, rule: &Rule, rules_store: &mut RuleStore, parser: &mut Parser,
     scope_query: &Option<String>,
-  ) {
+  ) -> Result<(), PiranhaError> {
+    // The number of rewrites performed by this application of the rule
+    let mut number_of_rewrites = 0;
     loop {
-      if !self._apply_rule(rule.clone(), rules_store, parser, scope_query) {
+      rules_store.check_deadline()?;
+      rules_store.set_parser_timeout(parser);
+      if !self._apply_rule(rule, rules_store, parser, scope_query, number_of_rewrites)? {
         break;
       }
+      number_of_rewrites += 1;
     }
+    Ok(())
   }
 
   /// Applies the rule to the first match in the source code
@@ -140,6 +180,7 @@ impl SourceCodeUnit {
   /// Parameters:
   /// * `rule` : the rule to be applied
   /// * `rule_store`: contains the input rule graph.
+  /// * `number_of_rewrites`: the number of rewrites already performed by this application of the rule.
   ///
   /// Algorithm:
   /// * check if the rule is match only
@@ -155,10 +196,10 @@ impl SourceCodeUnit {
   /// *** Update the substitution table
   /// *** Propagate the change
   fn _apply_rule(
-    &mut self, rule: Rule, rule_store: &mut RuleStore, parser: &mut Parser,
-    scope_query: &Option<String>,
-  ) -> bool {
-    let scope_node = self.get_scope_node(scope_query, rule_store);
+    &mut self, rule: &Rule, rule_store: &mut RuleStore, parser: &mut Parser,
+    scope_query: &Option<String>, number_of_rewrites: usize,
+  ) -> Result<bool, PiranhaError> {
+    let scope_node = self.get_scope_node(scope_query, rule_store)?;
 
     let mut query_again = false;
 
@@ -167,17 +208,9 @@ impl SourceCodeUnit {
     // Add mappings to the substitution
     // Propagate each applied edit. The next rule will be applied relative to the application of this edit.
     if !rule.is_match_only_rule() {
-      if let Some(edit) = rule.get_edit(&self.clone(), rule_store, scope_node, true) {
-        self.rewrites_mut().push(edit.clone());
+      if let Some(edit) = rule.get_edit(self, rule_store, scope_node, true)? {
         query_again = true;
-
-        // Add all the (code_snippet, tag) mapping to the substitution table.
-        self.add_to_substitutions(edit.matches(), rule_store);
-
-        // Apply edit_1
-        let applied_ts_edit = self.apply_edit(&edit, parser);
-
-        self.propagate(get_replace_range(applied_ts_edit), rule, rule_store, parser);
+        self.apply_rewrite(rule, edit, number_of_rewrites, rule_store, parser)?;
       }
     }
     // When rule is a "match-only" rule :
@@ -186,21 +219,45 @@ impl SourceCodeUnit {
     // Propagate each match. Note that,  we pass a identity edit (where old range == new range) in to the propagate logic.
     // The next edit will be applied relative to the identity edit.
     else {
-      for m in rule.get_matches(&self.clone(), rule_store, scope_node, true) {
-        self.matches_mut().push((rule.name(), m.clone()));
-
-        // In this scenario we pass the match and replace range as the range of the match `m`
-        // This is equivalent to propagating an identity rule
-        //  i.e. a rule that replaces the matched code with itself
-        // Note that, here we DO NOT invoke the `_apply_edit` method and only update the `substitutions`
-        // By NOT invoking this we simulate the application of an identity rule
-        //
-        self.add_to_substitutions(m.matches(), rule_store);
-
-        self.propagate(m.range(), rule.clone(), rule_store, parser);
+      for m in rule.get_matches(self, rule_store, scope_node, true)? {
+        self.apply_match(rule, m, rule_store, parser)?;
       }
     }
-    query_again
+    Ok(query_again)
+  }
+
+  /// Applies the `edit` (i.e. the rewrite of the first match of `rule`) and propagates it.
+  fn apply_rewrite(
+    &mut self, rule: &Rule, edit: Edit, number_of_rewrites: usize, rule_store: &mut RuleStore,
+    parser: &mut Parser,
+  ) -> Result<(), PiranhaError> {
+    self.check_rewrite_limits(&edit, number_of_rewrites, rule_store)?;
+    self.rewrites_mut().push(edit.clone());
+
+    // Add all the (code_snippet, tag) mapping to the substitution table.
+    self.add_to_substitutions(edit.matches(), rule_store);
+
+    // Apply edit_1
+    let applied_ts_edit = self.apply_edit(&edit, parser)?;
+
+    self.propagate(get_replace_range(applied_ts_edit), rule, rule_store, parser)
+  }
+
+  /// Records the match `m` of the match-only `rule` and propagates it.
+  fn apply_match(
+    &mut self, rule: &Rule, m: Match, rule_store: &mut RuleStore, parser: &mut Parser,
+  ) -> Result<(), PiranhaError> {
+    self.matches_mut().push((rule.name(), m.clone()));
+
+    // In this scenario we pass the match and replace range as the range of the match `m`
+    // This is equivalent to propagating an identity rule
+    //  i.e. a rule that replaces the matched code with itself
+    // Note that, here we DO NOT invoke the `_apply_edit` method and only update the `substitutions`
+    // By NOT invoking this we simulate the application of an identity rule
+    //
+    self.add_to_substitutions(m.matches(), rule_store);
+
+    self.propagate(m.range(), rule, rule_store, parser)
   }
 
   /// This is the propagation logic of the Piranha's main algorithm.
@@ -220,17 +277,19 @@ impl SourceCodeUnit {
   ///  (iv) Apply the rules based on custom language specific scopes (as defined in `<language>/scope_config.toml`) (recursive)
   ///
   fn propagate(
-    &mut self, replace_range: Range, rule: Rule, rules_store: &mut RuleStore, parser: &mut Parser,
-  ) {
+    &mut self, replace_range: Range, rule: &Rule, rules_store: &mut RuleStore, parser: &mut Parser,
+  ) -> Result<(), PiranhaError> {
     let mut current_replace_range = replace_range;
 
     let mut current_rule = rule.name();
     let mut next_rules_stack: VecDeque<(String, Rule)> = VecDeque::new();
+    // The number of rewrites performed by each "Parent" scoped rule during this propagation
+    let mut number_of_parent_rewrites: HashMap<String, usize> = HashMap::new();
     // Perform the parent edits, while queueing the Method and Class level edits.
     // let file_level_scope_names = [METHOD, CLASS];
     loop {
       // Get all the (next) rules that could be after applying the current rule (`rule`).
-      let next_rules_by_scope = rules_store.get_next(&current_rule, self.substitutions());
+      let next_rules_by_scope = rules_store.get_next(&current_rule, self.substitutions())?;
 
       debug!(
         "\n{}",
@@ -246,10 +305,11 @@ impl SourceCodeUnit {
       // Adds rules of scope != ["Parent", "Global"] to the stack
       self.add_rules_to_stack(
         &next_rules_by_scope,
+        &current_rule,
         current_replace_range,
         rules_store,
         &mut next_rules_stack,
-      );
+      )?;
 
       // Add Global rules as seed rules
       for r in &next_rules_by_scope[GLOBAL] {
@@ -259,12 +319,17 @@ impl SourceCodeUnit {
       // Process the parent
       // Find the rules to be applied in the "Parent" scope that match any parent (context) of the changed node in the previous edit
       if let Some(edit) = Rule::get_edit_for_context(
-        &self.clone(),
+        self,
         current_replace_range.start_byte,
         current_replace_range.end_byte,
         rules_store,
         &next_rules_by_scope[PARENT],
-      ) {
+      )? {
+        let number_of_rewrites = number_of_parent_rewrites
+          .entry(edit.matched_rule())
+          .or_default();
+        self.check_rewrite_limits(&edit, *number_of_rewrites, rules_store)?;
+        *number_of_rewrites += 1;
         self.rewrites_mut().push(edit.clone());
         debug!(
           "\n{}",
@@ -275,7 +340,7 @@ impl SourceCodeUnit {
           .green()
         );
         // Apply the matched rule to the parent
-        let applied_edit = self.apply_edit(&edit, parser);
+        let applied_edit = self.apply_edit(&edit, parser)?;
         current_replace_range = get_replace_range(applied_edit);
         current_rule = edit.matched_rule();
         // Add the (tag, code_snippet) mapping to substitution table.
@@ -288,65 +353,224 @@ impl SourceCodeUnit {
 
     // Apply the next rules from the stack
     for (sq, rle) in &next_rules_stack {
-      self.apply_rule(rle.clone(), rules_store, parser, &Some(sq.to_string()));
+      self.apply_rule(rle, rules_store, parser, &Some(sq.to_string()))?;
     }
+    Ok(())
   }
 
   /// Adds the "Method" and "Class" scoped next rules to the queue.
+  /// Returns `ScopeNotFound` if the change made by `current_rule` is not enclosed by the scope of a next rule.
   fn add_rules_to_stack(
-    &mut self, next_rules_by_scope: &HashMap<String, Vec<Rule>>, current_match_range: Range,
-    rules_store: &mut RuleStore, stack: &mut VecDeque<(String, Rule)>,
-  ) {
+    &mut self, next_rules_by_scope: &HashMap<String, Vec<Rule>>, current_rule: &str,
+    current_match_range: Range, rules_store: &mut RuleStore, stack: &mut VecDeque<(String, Rule)>,
+  ) -> Result<(), PiranhaError> {
     for (scope_level, rules) in next_rules_by_scope {
       // Scope level is not "PArent" or "Global"
       if ![PARENT, GLOBAL].contains(&scope_level.as_str()) {
         for rule in rules {
           let scope_query = ScopeGenerator::get_scope_query(
-            self.clone(),
+            self,
             scope_level,
             current_match_range.start_byte,
             current_match_range.end_byte,
             rules_store,
-          );
+          )?
+          .ok_or_else(|| {
+            let edit = Edit::new(
+              Match::new(current_match_range, HashMap::new()),
+              self.code()[current_match_range.start_byte..current_match_range.end_byte].to_string(),
+              current_rule.to_string(),
+            );
+            let message = format!(
+              "Could not find the enclosing {} scope of the change made by rule {} (to apply rule {})",
+              scope_level,
+              current_rule,
+              rule.name()
+            );
+            PiranhaError::ScopeNotFound(Box::new(Diagnostic::new(
+              current_rule.to_string(),
+              edit,
+              message,
+            )))
+          })?;
           // Add Method and Class scoped rules to the queue
-          stack.push_front((scope_query, rule.instantiate(self.substitutions())));
+          stack.push_front((scope_query, rule.instantiate(self.substitutions())?));
         }
       }
     }
+    Ok(())
+  }
+
+  /// Checks that performing `edit` does not exceed the number of rewrites allowed per rule and per file
+  /// (i.e. `max_rewrites_per_rule` and `max_rewrites_per_file`), given the `number_of_rewrites` already
+  /// performed by the current application of the rule.
+  /// Exceeding these limits usually indicates that the rule application does not terminate, for instance
+  /// because of a cycle in the rule graph or a rule whose replacement matches its own query.
+  fn check_rewrite_limits(
+    &self, edit: &Edit, number_of_rewrites: usize, rules_store: &RuleStore,
+  ) -> Result<(), PiranhaError> {
+    let rule_name = edit.matched_rule();
+    let exceeded_limit = if number_of_rewrites >= rules_store.max_rewrites_per_rule() {
+      format!(
+        "Rule {} exceeded the limit of {} rewrites per rule (max_rewrites_per_rule) in {:?}.",
+        rule_name,
+        rules_store.max_rewrites_per_rule(),
+        self.path()
+      )
+    } else if self.rewrites().len() >= rules_store.max_rewrites_per_file() {
+      format!(
+        "Rule {} exceeded the limit of {} rewrites per file (max_rewrites_per_file) in {:?}.",
+        rule_name,
+        rules_store.max_rewrites_per_file(),
+        self.path()
+      )
+    } else {
+      return Ok(());
+    };
+
+    let cause = match rules_store.get_cycle(&rule_name) {
+      Some(cycle) => format!(
+        "The rule is a part of the cycle {} in the rule graph.",
+        cycle.join(" -> ")
+      ),
+      None => "The replacement of the rule probably matches its own query.".to_string(),
+    };
+    Err(PiranhaError::RewriteLimitExceeded(Box::new(
+      Diagnostic::new(
+        rule_name,
+        edit.clone(),
+        format!("{} {}", exceeded_limit, cause),
+      ),
+    )))
   }
 
-  fn get_scope_node(&self, scope_query: &Option<String>, rules_store: &mut RuleStore) -> Node {
+  fn get_scope_node(
+    &self, scope_query: &Option<String>, rules_store: &mut RuleStore,
+  ) -> Result<Node<'_>, PiranhaError> {
     // Get scope node
     // let mut scope_node = self.root_node();
     if let Some(query_str) = scope_query {
       // Apply the scope query in the source code and get the appropriate node
-      let tree_sitter_scope_query = rules_store.query(query_str);
+      let tree_sitter_scope_query = &rules_store.query(query_str)?;
       if let Some(p_match) =
         &self
           .root_node()
-          .get_match_for_query(&self.code(), tree_sitter_scope_query, true)
+          .get_match_for_query(self.code(), tree_sitter_scope_query, true)
       {
-        return get_node_for_range(
+        return Ok(get_node_for_range(
           self.root_node(),
           p_match.range().start_byte,
           p_match.range().end_byte,
-        );
+        ));
       }
     }
-    self.root_node()
+    Ok(self.root_node())
   }
 
   /// Apply all `rules` sequentially.
+  ///
+  /// The application of each rule is transactional. If applying the rule (or any cleanup rule it triggers)
+  /// produces syntactically incorrect code, exceeds the rewrite limits or has no enclosing scope for a next rule, the source code unit is rolled back
+  /// to its state before the rule was applied, the failure is recorded as a diagnostic, and the remaining rules are applied.
+  /// Any other error (e.g. an invalid rule) is returned.
+  ///
+  /// The `rules` are matched together in a single pass (using a combined query), and each rule is applied to its
+  /// matches (the rules without any match are skipped).
+  /// After each change, only the changed range of the source code is re-queried (see `CombinedMatches::update`).
   fn apply_rules(
     &mut self, rules_store: &mut RuleStore, rules: &[Rule], parser: &mut Parser,
     scope_query: Option<String>,
-  ) {
-    for rule in rules {
-      self.apply_rule(rule.to_owned(), rules_store, parser, &scope_query)
+  ) -> Result<(), PiranhaError> {
+    let combined_query = CombinedQuery::new(rules, rules_store)?;
+    let scope = self.get_scope_node(&scope_query, rules_store)?.byte_range();
+    let mut matches = combined_query.get_matches(self, scope);
+    for (index, rule) in rules.iter().enumerate() {
+      if matches.of(index).next().is_none() {
+        continue;
+      }
+      let checkpoint = self.checkpoint();
+      match self.apply_rule_to_matches(rule, index, &mut matches, rules_store, parser, &scope_query)
+      {
+        Err(PiranhaError::SyntacticallyIncorrectRewrite(diagnostic)) => {
+          #[rustfmt::skip]
+          warn!("{}", format!("Rolling back the application of rule {} to {:?}, since the rule {} produced syntactically incorrect code", rule.name(), self.path(), diagnostic.rule_name()).red());
+          debug!("{}", diagnostic.message());
+          self.rollback(checkpoint);
+          self.diagnostics_mut().push(*diagnostic);
+        }
+        Err(
+          PiranhaError::RewriteLimitExceeded(diagnostic) | PiranhaError::ScopeNotFound(diagnostic),
+        ) => {
+          #[rustfmt::skip]
+          warn!("{}", format!("Rolling back the application of rule {} to {:?}. {}", rule.name(), self.path(), diagnostic.message()).red());
+          self.rollback(checkpoint);
+          self.diagnostics_mut().push(*diagnostic);
+        }
+        result => {
+          result?;
+          continue;
+        }
+      }
+      // The matches of the rolled back code are recomputed
+      let scope = self.get_scope_node(&scope_query, rules_store)?.byte_range();
+      matches = combined_query.get_matches(self, scope);
+    }
+    Ok(())
+  }
+
+  /// Applies the `rule` (i.e. the rule at `index` in the group of the combined query) to its `matches`.
+  /// Like `apply_rule`, a rewrite rule is applied to its first match (satisfying its constraints) until no such match
+  /// is left, while all the matches of a match-only rule are recorded.
+  /// After each change, the `matches` are updated by re-querying only the changed range.
+  fn apply_rule_to_matches(
+    &mut self, rule: &Rule, index: usize, matches: &mut CombinedMatches,
+    rules_store: &mut RuleStore, parser: &mut Parser, scope_query: &Option<String>,
+  ) -> Result<(), PiranhaError> {
+    if rule.is_match_only_rule() {
+      let old_code = self.code().to_string();
+      let mut rule_matches = vec![];
+      for p_match in matches.of(index) {
+        if rule.is_satisfied_by(self, rules_store, p_match)? {
+          rule_matches.push(p_match.clone());
+        }
+      }
+      for p_match in rule_matches {
+        self.apply_match(rule, p_match, rules_store, parser)?;
+      }
+      let scope = self.get_scope_node(scope_query, rules_store)?.byte_range();
+      matches.update(self, &old_code, scope);
+      return Ok(());
+    }
+
+    // The number of rewrites performed by this application of the rule
+    let mut number_of_rewrites = 0;
+    loop {
+      rules_store.check_deadline()?;
+      rules_store.set_parser_timeout(parser);
+      let mut edit = None;
+      for p_match in matches.of(index) {
+        if rule.is_satisfied_by(self, rules_store, p_match)? {
+          edit = Some(rule.get_edit_for_match(p_match)?);
+          break;
+        }
+      }
+      let edit = match edit {
+        Some(edit) => edit,
+        None => break,
+      };
+      let old_code = self.code().to_string();
+      self.apply_rewrite(rule, edit, number_of_rewrites, rules_store, parser)?;
+      let scope = self.get_scope_node(scope_query, rules_store)?.byte_range();
+      matches.update(self, &old_code, scope);
+      number_of_rewrites += 1;
     }
+    Ok(())
   }
 }
 
+// A processed file, along with either its updated source code unit and rule store, or the reason it was skipped.
+type ProcessedFile = (PathBuf, Result<(SourceCodeUnit, RuleStore), String>);
+
 // Maintains the state of Piranha and the updated content of files in the source code.
 struct FlagCleaner {
   // Maintains Piranha's state
@@ -355,6 +579,13 @@ struct FlagCleaner {
   path_to_codebase: String,
   // Files updated by Piranha.
   relevant_files: HashMap<PathBuf, SourceCodeUnit>,
+  // The (current) content of each file (with the language appropriate file extension) in the code base.
+  // It is populated by walking the code base once, and updated as the files are rewritten.
+  file_index: HashMap<PathBuf, String>,
+  // Files skipped by Piranha (because they exceeded a limit), along with the reason.
+  skipped_files: HashMap<PathBuf, String>,
+  // The time by which the whole run has to complete (i.e. `global_timeout`).
+  global_deadline: Option<(Instant, String)>,
 }
 
 impl FlagCleaner {
@@ -362,125 +593,323 @@ impl FlagCleaner {
     self
       .relevant_files
       .values()
-      .filter(|r| !r.matches().is_empty() || !r.rewrites().is_empty())
+      .filter(|r| {
+        !r.matches().is_empty() || !r.rewrites().is_empty() || !r.diagnostics().is_empty()
+      })
       .cloned()
       .collect_vec()
   }
 
+  /// Gets the summaries of the updated files, followed by the summaries of the skipped files.
+  fn get_summaries(&self) -> Vec<PiranhaOutputSummary> {
+    let mut summaries = self
+      .get_updated_files()
+      .iter()
+      .map(PiranhaOutputSummary::new)
+      .collect_vec();
+    for (path, reason) in self.skipped_files.iter().sorted() {
+      summaries.push(PiranhaOutputSummary::new_skipped(path, reason.to_string()));
+    }
+    summaries
+  }
+
   /// Performs cleanup related to stale flags
-  fn perform_cleanup(&mut self) {
-    // Setup the parser for the specific language
-    let mut parser = Parser::new();
-    parser
-      .set_language(self.rule_store.language())
-      .expect("Could not set the language for the parser.");
-
-    // Keep looping until new `global` rules are added.
-    loop {
-      let current_rules = self.rule_store.global_rules();
+  ///
+  /// This is a work-list algorithm. Initially, the work-list contains the seed (global) rules.
+  /// In each iteration, the rules in the work-list are applied to the files (from the `file_index`) that match their grep heuristics.
+  /// The work-list for the next iteration contains the global rules discovered in this iteration.
+  /// This way, each file is only revisited when a newly added global rule could apply to it.
+  ///
+  /// The files are processed in parallel, each worker thread using its own parser.
+  /// Each file is processed with its own copy of the rule store (sharing the query cache).
+  /// Once all the files are processed, the global rules and global tags discovered in each file are merged into
+  /// the rule store in the order of the file paths. This makes the result independent of the thread scheduling.
+  /// Since the source code unit of a file is created with the global tags discovered so far, a file read in an iteration
+  /// could miss the global tags discovered by the other files of this iteration. Such a file is processed again
+  /// (from scratch) with these global tags, until no file misses a global tag (i.e. until a fixpoint is reached).
+  ///
+  /// A file exceeding `max_file_size`, `file_timeout` or `global_timeout` is skipped: it is left untouched (i.e. all
+  /// its updates, including those of the previous iterations, are discarded), it is not revisited and it is reported as skipped.
+  fn perform_cleanup(&mut self) -> Result<(), PiranhaError> {
+    // Check if a parser can be setup for the specific language
+    let language_name = self.rule_store.language_name().to_string();
+    get_parser(language_name.to_string())?;
+
+    self.index_code_base()?;
+
+    let mut work_list = self.rule_store.global_rules();
+    // Keep looping until no new `global` rules are added.
+    while !work_list.is_empty() {
+      let number_of_global_rules = self.rule_store.global_rules().len();
+
+      debug!("\n # Global rules to apply {}", work_list.len());
+      // Get each file containing the usage of the feature flag API, along with
+      // its source code unit from the cache `relevant_files` (if any).
+      let files = self
+        .get_files_containing_feature_flag_api_usage(&work_list)?
+        .into_iter()
+        .filter(|path| !self.skipped_files.contains_key(path))
+        .map(|path| {
+          let source_code_unit = self.relevant_files.remove(&path);
+          (path, source_code_unit)
+        })
+        .collect_vec();
+
+      // The source code units of the files read in this iteration are created with the global tags discovered so far
+      let new_files = files
+        .iter()
+        .filter(|(_, source_code_unit)| source_code_unit.is_none())
+        .map(|(path, _)| path.to_path_buf())
+        .collect::<HashSet<_>>();
+      let files = files
+        .into_iter()
+        .map(|(path, source_code_unit)| (path, source_code_unit, self.rule_store.clone()))
+        .collect_vec();
+      let mut processed_files = self.process_files(files, &work_list)?;
+      // Process the new files missing the global tags discovered by the other files again, until a fixpoint is reached
+      loop {
+        processed_files.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let files = self.get_files_missing_global_tags(&processed_files, &new_files);
+        if files.is_empty() {
+          break;
+        }
+        #[rustfmt::skip]
+        debug!("{}", format!("Found new global tags. Will process {} files again.", files.len()).green());
+        let reprocessed_files = self.process_files(files, &work_list)?;
+        processed_files.retain(|(path, _)| !reprocessed_files.iter().any(|(p, _)| p == path));
+        processed_files.extend(reprocessed_files);
+      }
 
-      debug!("\n # Global rules {}", current_rules.len());
-      // Iterate over each file containing the usage of the feature flag API
-      for (path, content) in self.get_files_containing_feature_flag_api_usage() {
+      // Merge the results in a deterministic order (i.e. the order of the file paths)
+      for (path, result) in processed_files {
+        let (source_code_unit, file_rule_store) = match result {
+          Ok(processed_file) => processed_file,
+          Err(reason) => {
+            // The file is left untouched, i.e. its source code unit (and its updates) is dropped
+            #[rustfmt::skip]
+            warn!("{}", format!("Skipping {:?} : {}", path, reason).red());
+            self.skipped_files.insert(path, reason);
+            continue;
+          }
+        };
         self
-          .relevant_files
-          // Get the content of the file for `path` from the cache `relevant_files`
-          .entry(path.to_path_buf())
-          // Populate the cache (`relevant_files`) with the content, in case of cache miss (lazily)
-          .or_insert_with(|| {
-            // Create new source code unit
-            SourceCodeUnit::new(
-              &mut parser,
-              content,
-              &self.rule_store.default_substitutions(),
-              path.as_path(),
-              self.rule_store.piranha_args(),
+          .rule_store
+          .merge_global_rules_and_tags(&file_rule_store);
+        self
+          .file_index
+          .insert(path.to_path_buf(), source_code_unit.code().to_string());
+        self.relevant_files.insert(path, source_code_unit);
+      }
+
+      // The next iteration only applies the newly added global rules.
+      work_list = self.rule_store.global_rules()[number_of_global_rules..].to_vec();
+      if !work_list.is_empty() {
+        debug!("Found new global rules. Will revisit the files matching their grep heuristics.");
+      }
+    }
+    Ok(())
+  }
+
+  /// Applies the `rules` to the `files` in parallel, each file with its own copy of the rule store.
+  /// The source code unit of a file is created (with the default substitutions of its rule store), if it is not cached.
+  /// Returns for each file either its updated source code unit along with its rule store, or the reason it was skipped.
+  fn process_files(
+    &self, files: Vec<(PathBuf, Option<SourceCodeUnit>, RuleStore)>, rules: &[Rule],
+  ) -> Result<Vec<ProcessedFile>, PiranhaError> {
+    let rule_store = &self.rule_store;
+    let language_name = rule_store.language_name();
+    let file_index = &self.file_index;
+    let global_deadline = &self.global_deadline;
+    files
+      .into_par_iter()
+      .map_init(
+        || get_parser(language_name.to_string()),
+        |parser, (path, source_code_unit, mut file_rule_store)| {
+          let parser = parser
+            .as_mut()
+            .map_err(|_| PiranhaError::UnsupportedLanguage(language_name.to_string()))?;
+          let args = rule_store.piranha_args();
+          let content = &file_index[&path];
+
+          // The deadline for this file is the earliest of its own deadline and the global deadline
+          let file_deadline = args.file_timeout().map(|timeout| {
+            (
+              Instant::now() + Duration::from_secs(timeout),
+              format!("exceeded file_timeout ({} seconds)", timeout),
             )
-          })
+          });
+          let deadline = [file_deadline, global_deadline.clone()]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(instant, _)| *instant);
+          file_rule_store.set_deadline(deadline);
+          if let Err(PiranhaError::TimeoutExceeded(reason)) = file_rule_store.check_deadline() {
+            return Ok((path, Err(reason)));
+          }
+          // The parser of this thread is reused across files, hence its timeout has to be (re)set for each file
+          file_rule_store.set_parser_timeout(parser);
+
+          // Populate the cache with a new source code unit, in case of cache miss (lazily)
+          let mut source_code_unit = match source_code_unit {
+            Some(source_code_unit) => source_code_unit,
+            None => match SourceCodeUnit::new(
+              parser,
+              content.to_string(),
+              &file_rule_store.default_substitutions(),
+              path.as_path(),
+              args,
+            ) {
+              Ok(source_code_unit) => source_code_unit,
+              Err(PiranhaError::TimeoutExceeded(reason)) => return Ok((path, Err(reason))),
+              Err(error) => return Err(error),
+            },
+          };
           // Apply the rules to this file
-          .apply_rules(&mut self.rule_store, &current_rules, &mut parser, None);
+          match source_code_unit.apply_rules(&mut file_rule_store, rules, parser, None) {
+            Err(PiranhaError::TimeoutExceeded(reason)) => Ok((path, Err(reason))),
+            result => {
+              result?;
+              Ok((path, Ok((source_code_unit, file_rule_store))))
+            }
+          }
+        },
+      )
+      .collect()
+  }
 
-        // Break when a new `global` rule is added
-        if self.rule_store.global_rules().len() > current_rules.len() {
-          debug!("Found a new global rule. Will start scanning all the files again.");
-          break;
+  /// Gets the new files (i.e. the files whose source code unit was created in the current iteration) that miss some
+  /// global tag discovered by the other `processed_files`, along with a copy of the rule store containing these global tags.
+  /// Note that the source code units of the other files are not affected by the global tags discovered after their creation.
+  fn get_files_missing_global_tags(
+    &self, processed_files: &[ProcessedFile], new_files: &HashSet<PathBuf>,
+  ) -> Vec<(PathBuf, Option<SourceCodeUnit>, RuleStore)> {
+    let mut files = vec![];
+    let rule_stores = processed_files
+      .iter()
+      .filter_map(|(path, result)| Some((path, &result.as_ref().ok()?.1)))
+      .collect_vec();
+    for (path, file_rule_store) in &rule_stores {
+      if !new_files.contains(*path) {
+        continue;
+      }
+      let mut global_tags = file_rule_store.global_tags().clone();
+      let mut is_missing_global_tags = false;
+      for (_, other_rule_store) in rule_stores.iter().filter(|(p, _)| p != path) {
+        for (tag, value) in other_rule_store.global_tags() {
+          if !global_tags.contains_key(tag) {
+            global_tags.insert(tag.to_string(), value.to_string());
+            is_missing_global_tags = true;
+          }
         }
       }
-      // If no new `global_rules` were added, break.
-      if self.rule_store.global_rules().len() == current_rules.len() {
-        break;
+      if is_missing_global_tags {
+        let mut rule_store = self.rule_store.clone();
+        rule_store.add_global_tags(&global_tags);
+        files.push((path.to_path_buf(), None, rule_store));
       }
     }
+    files
   }
 
-  /// Gets all the files from the code base that (i) have the language appropriate file extension, and (ii) contains the grep pattern.
-  /// Note that `WalkDir` traverses the directory with parallelism.
-  /// If all the global rules have no holes (i.e. we will have no grep patterns), we will try to find a match for each global rule in every file in the target.
-  fn get_files_containing_feature_flag_api_usage(&self) -> HashMap<PathBuf, String> {
-    let no_global_rules_with_holes = self
-      .rule_store
-      .global_rules()
-      .iter()
-      .any(|x| x.holes().is_empty());
-    let pattern = self.get_grep_heuristics();
-    let files: HashMap<PathBuf, String> = WalkDir::new(&self.path_to_codebase)
-      // Walk over the entire code base
-      .into_iter()
+  /// Walks the code base and reads all the files with the language appropriate file extension.
+  /// The files ignored by `.gitignore` or `.ignore` files, the (hidden) files starting with `.`, and the files
+  /// that do not satisfy the `include` and `exclude` globs (matched against the path relative to the code base) are skipped.
+  /// The files exceeding `max_file_size` are not read, but reported as skipped.
+  /// Note that the files are read in parallel. A file that cannot be read is skipped (with a warning).
+  fn index_code_base(&mut self) -> Result<(), PiranhaError> {
+    let args = self.rule_store.piranha_args();
+    let include = get_glob_set(args.include())?;
+    let exclude = get_glob_set(args.exclude())?;
+    let extensions = args.language_name().get_extensions();
+    let paths = WalkBuilder::new(&self.path_to_codebase)
+      // Honour the `.gitignore` files even if the code base is not a git repository
+      .require_git(false)
+      .build()
       // Ignore errors
       .filter_map(|e| e.ok())
-      // Filter files with the desired extension
-      .filter(|de| {
-        de.path()
+      .filter(|de| de.file_type().filter(|ft| ft.is_file()).is_some())
+      .map(|de| de.into_path())
+      // Filter files with the desired extension(s)
+      .filter(|path| {
+        path
           .extension()
-          .and_then(|e| {
-            e.to_str()
-              .filter(|x| x.eq(&self.rule_store.language_name()))
-          })
+          .and_then(|e| e.to_str().filter(|x| extensions.contains(x)))
           .is_some()
       })
-      // Read the file
-      .map(|f| (f.path(), read_file(&f.path()).unwrap()))
-      // Filter the files containing the desired regex pattern
-      .filter(|x| no_global_rules_with_holes || pattern.is_match(x.1.as_str()))
+      // Filter the files satisfying the `include` and `exclude` globs
+      .filter(|path| {
+        let relative_path = path.strip_prefix(&self.path_to_codebase).unwrap_or(path);
+        (args.include().is_empty() || include.is_match(relative_path))
+          && !exclude.is_match(relative_path)
+      })
+      .collect_vec();
+
+    // The files exceeding `max_file_size` are skipped (without reading them)
+    let mut paths_to_read = vec![];
+    for path in paths {
+      match (args.max_file_size(), fs::metadata(&path)) {
+        (Some(max_file_size), Ok(metadata)) if metadata.len() > max_file_size as u64 => {
+          #[rustfmt::skip]
+          let reason = format!("the file size ({} bytes) exceeds max_file_size ({} bytes)", metadata.len(), max_file_size);
+          #[rustfmt::skip]
+          warn!("{}", format!("Skipping {:?} : {}", path, reason).red());
+          self.skipped_files.insert(path, reason);
+        }
+        _ => paths_to_read.push(path),
+      }
+    }
+
+    let files: HashMap<PathBuf, String> = paths_to_read
+      // Read the files
+      .into_par_iter()
+      .filter_map(|path| match read_file(&path) {
+        Ok(content) => Some((path, content)),
+        Err(message) => {
+          #[rustfmt::skip]
+          warn!("{}", format!("Could not read {:?} : {}. Skipping it.", path, message).red());
+          None
+        }
+      })
       .collect();
     #[rustfmt::skip]
+    debug!("{}", format!("Indexed {} files.", files.len()).green());
+    self.file_index = files;
+    Ok(())
+  }
+
+  /// Gets all the files from the `file_index` that match the grep heuristics of the given `rules`.
+  /// If any of the `rules` has no grep heuristics, we will try to find a match for the `rules` in every file in the target.
+  fn get_files_containing_feature_flag_api_usage(
+    &self, rules: &[Rule],
+  ) -> Result<Vec<PathBuf>, PiranhaError> {
+    let grep_heuristics = GrepHeuristics::new(rules)?;
+    let files = self
+      .file_index
+      .iter()
+      // Filter the files matching the grep heuristics
+      .filter(|(_, content)| grep_heuristics.is_match(content.as_str()))
+      .map(|(path, _)| path.to_path_buf())
+      .collect_vec();
+    #[rustfmt::skip]
     debug!("{}", format!("Will parse and analyze {} files.", files.len()).green());
-    files
+    Ok(files)
   }
 
   /// Instantiate Flag-cleaner
-  fn new(args: &PiranhaArguments) -> Self {
-    let graph_rule_store = RuleStore::new(args);
-    Self {
+  fn new(args: &PiranhaArguments) -> Result<Self, PiranhaError> {
+    let graph_rule_store = RuleStore::new(args)?;
+    Ok(Self {
       rule_store: graph_rule_store,
       path_to_codebase: String::from(args.path_to_code_base()),
       relevant_files: HashMap::new(),
-    }
-  }
-
-  /// To create the current set of global rules, certain substitutions were applied.
-  /// This method creates a regex pattern matching these substituted values.
-  ///
-  /// At the directory level, we would always look to perform global rules. However this is expensive because
-  /// it requires parsing each file. To overcome this, we apply this simple
-  /// heuristic to find the (upper bound) files that would match one of our current global rules.
-  /// This heuristic reduces the number of files to parse.
-  ///
-  fn get_grep_heuristics(&self) -> Regex {
-    let reg_x = self
-      .rule_store
-      .global_rules()
-      .iter()
-      .flat_map(|r| r.grep_heuristics())
-      .sorted()
-      //Remove duplicates
-      .dedup()
-      //FIXME: Dirty trick to remove true and false. Ideally, grep heuristic could be a field in itself for a rule.
-      // Since not all "holes" could be used as grep heuristic.
-      .filter(|x| {
-        !x.is_empty() && !x.to_lowercase().eq("true") && !x.to_lowercase().as_str().eq("false")
-      })
-      .join("|");
-    Regex::new(reg_x.as_str()).unwrap()
+      file_index: HashMap::new(),
+      skipped_files: HashMap::new(),
+      global_deadline: args.global_timeout().map(|timeout| {
+        (
+          Instant::now() + Duration::from_secs(timeout),
+          format!("exceeded global_timeout ({} seconds)", timeout),
+        )
+      }),
+    })
   }
 }
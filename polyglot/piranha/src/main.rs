@@ -12,12 +12,12 @@ Copyright (c) 2022 Uber Technologies, Inc.
 */
 
 //! Defines the entry-point for Piranha.
-use std::{fs, time::Instant};
+use std::{fs, process, time::Instant};
 
 use log::{debug, info};
 use polyglot_piranha::{
   execute_piranha, models::piranha_arguments::PiranhaArguments,
-  models::piranha_output::PiranhaOutputSummary,
+  models::piranha_error::PiranhaError, models::piranha_output::PiranhaOutputSummary,
 };
 
 fn main() {
@@ -26,16 +26,25 @@ fn main() {
 
   info!("Executing Polyglot Piranha");
 
-  let args = PiranhaArguments::from_command_line();
+  if let Err(error) = run() {
+    eprintln!("{}", error);
+    process::exit(1);
+  }
+
+  info!("Time elapsed - {:?}", now.elapsed().as_secs());
+}
+
+/// Runs Piranha with the command line arguments, and writes the output summaries (if requested).
+fn run() -> Result<(), PiranhaError> {
+  let args = PiranhaArguments::from_command_line()?;
 
   debug!("Piranha Arguments are \n{:#?}", args);
-  let piranha_output_summaries = execute_piranha(&args, true);
+  let piranha_output_summaries = execute_piranha(&args, true)?;
 
   if let Some(path) = args.path_to_output_summaries() {
     write_output_summary(piranha_output_summaries, path);
   }
-
-  info!("Time elapsed - {:?}", now.elapsed().as_secs());
+  Ok(())
 }
 
 /// Writes the output summaries to a Json file named `path_to_output_summaries` .
@@ -0,0 +1,227 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use itertools::Itertools;
+use tree_sitter::{Node, Query, QueryCapture, QueryCursor};
+
+use crate::utilities::{
+  get_changed_range,
+  tree_sitter_utilities::{get_match_for_query_matches, get_node_for_range, TreeSitterHelpers},
+  MapOfVec,
+};
+
+use super::{
+  matches::Match, piranha_error::PiranhaError, rule::Rule, rule_store::RuleStore,
+  source_code_unit::SourceCodeUnit,
+};
+
+/// The queries of a group of rules compiled into a single (multi-pattern) tree-sitter query.
+///
+/// Matching the combined query traverses the tree once for the whole group (instead of once per rule),
+/// and each match is routed back to its rule by its pattern index.
+/// After a change to the source code, the matches are updated by re-querying only the changed range.
+pub(crate) struct CombinedQuery {
+  // The combined query
+  query: Arc<Query>,
+  // The index of the rule (in the group) to which each pattern of the combined query belongs
+  rule_index_by_pattern: Vec<usize>,
+  // The number of patterns of the query of each rule (a match of the rule has to match each of them)
+  pattern_count_by_rule: Vec<usize>,
+  // The tag names of the query of each rule (the matches of a rule only map the tags of its own query)
+  tag_names_by_rule: Vec<Vec<String>>,
+  // The replace node of each rule (if any)
+  replace_node_by_rule: Vec<Option<String>>,
+  // Whether the (multi-line) code snippets have to be re-indented (for indentation sensitive languages)
+  reindent: bool,
+}
+
+/// The matches of a combined query grouped by rule (i.e. by the index of the rule in the group).
+pub(crate) struct CombinedMatches<'a> {
+  // The combined query
+  combined_query: &'a CombinedQuery,
+  // The matches of each rule, along with the byte range of their outermost node.
+  // Like the matches of a rule's own query, the matches of each rule are sorted from bottom to top.
+  matches_by_rule: Vec<Vec<(Range<usize>, Match)>>,
+}
+
+impl CombinedQuery {
+  pub(crate) fn new(rules: &[Rule], rule_store: &mut RuleStore) -> Result<Self, PiranhaError> {
+    let mut queries = vec![];
+    let mut rule_index_by_pattern = vec![];
+    let mut pattern_count_by_rule = vec![];
+    let mut tag_names_by_rule = vec![];
+    let mut replace_node_by_rule = vec![];
+    for (index, rule) in rules.iter().enumerate() {
+      // A rule without a query (i.e. a dummy rule) has no matches
+      if rule.is_dummy_rule() {
+        pattern_count_by_rule.push(0);
+        tag_names_by_rule.push(vec![]);
+        replace_node_by_rule.push(None);
+        continue;
+      }
+      let query = rule.query()?;
+      // A rule's query can consist of multiple patterns
+      let rule_query = rule_store.query(&query)?;
+      rule_index_by_pattern.extend(vec![index; rule_query.pattern_count()]);
+      pattern_count_by_rule.push(rule_query.pattern_count());
+      tag_names_by_rule.push(rule_query.capture_names().to_vec());
+      replace_node_by_rule.push(if rule.is_match_only_rule() {
+        None
+      } else {
+        Some(rule.replace_node()?)
+      });
+      queries.push(query);
+    }
+    Ok(Self {
+      query: rule_store.query(&queries.iter().join("\n"))?,
+      rule_index_by_pattern,
+      pattern_count_by_rule,
+      tag_names_by_rule,
+      replace_node_by_rule,
+      reindent: rule_store
+        .language_name()
+        .to_string()
+        .is_indentation_sensitive(),
+    })
+  }
+
+  /// Gets the matches of each rule within the given byte `range` of the `source_code_unit`.
+  ///
+  /// Note that the constraints of the rules are not checked.
+  pub(crate) fn get_matches(
+    &self, source_code_unit: &SourceCodeUnit, range: Range<usize>,
+  ) -> CombinedMatches<'_> {
+    let mut matches = CombinedMatches {
+      combined_query: self,
+      matches_by_rule: vec![vec![]; self.pattern_count_by_rule.len()],
+    };
+    matches.add(source_code_unit, range);
+    matches
+  }
+}
+
+impl CombinedMatches<'_> {
+  /// Gets the matches of the rule at `rule_index` (in the group).
+  pub(crate) fn of(&self, rule_index: usize) -> impl Iterator<Item = &Match> {
+    self.matches_by_rule[rule_index]
+      .iter()
+      .map(|(_, p_match)| p_match)
+  }
+
+  /// Updates the matches after the source code of the `source_code_unit` changed from `old_code`.
+  /// The matches before the changed range are kept, the matches after it are shifted, and the other matches are dropped.
+  /// Then, only the changed range (within the byte range `scope`) is re-queried.
+  pub(crate) fn update(
+    &mut self, source_code_unit: &SourceCodeUnit, old_code: &str, scope: Range<usize>,
+  ) {
+    let changed_range = match get_changed_range(old_code, source_code_unit.code()) {
+      Some(changed_range) => changed_range,
+      None => return,
+    };
+    let delta = source_code_unit.code().len() as isize - old_code.len() as isize;
+    let shift = |byte: usize| (byte as isize + delta) as usize;
+    // The end of the changed range in the old code
+    let old_end = (changed_range.end as isize - delta) as usize;
+
+    let mut requery_range = changed_range.clone();
+    for rule_matches in &mut self.matches_by_rule {
+      let mut updated_rule_matches = vec![];
+      for (outermost_range, p_match) in rule_matches.drain(..) {
+        if outermost_range.end <= changed_range.start {
+          updated_rule_matches.push((outermost_range, p_match));
+        } else if outermost_range.start >= old_end {
+          let shifted_range = shift(outermost_range.start)..shift(outermost_range.end);
+          match shift_match(&p_match, source_code_unit.root_node(), delta) {
+            Some(shifted_match) => updated_rule_matches.push((shifted_range, shifted_match)),
+            // The code after the change is parsed differently, hence it has to be re-queried as well
+            None => requery_range.end = requery_range.end.max(shifted_range.end),
+          }
+        }
+      }
+      *rule_matches = updated_rule_matches;
+    }
+    let requery_range = requery_range.start.max(scope.start)..requery_range.end.min(scope.end);
+    if !requery_range.is_empty() {
+      self.add(source_code_unit, requery_range);
+    }
+  }
+
+  // Adds the matches within the given byte `range` (that are not already known).
+  fn add(&mut self, source_code_unit: &SourceCodeUnit, range: Range<usize>) {
+    let combined_query = self.combined_query;
+    let source_code = source_code_unit.code();
+    let mut cursor = QueryCursor::new();
+    cursor.set_byte_range(range);
+
+    // Like `get_all_matches_for_query`, we group the query match instances of each rule based on the range of
+    // the outermost node they matched.
+    let mut query_matches_by_rule_and_range: HashMap<_, Vec<Vec<QueryCapture>>> = HashMap::new();
+    for query_match in cursor.matches(
+      &combined_query.query,
+      source_code_unit.root_node(),
+      source_code.as_bytes(),
+    ) {
+      if let Some(captured_node) = query_match.captures.first() {
+        query_matches_by_rule_and_range.collect(
+          (
+            combined_query.rule_index_by_pattern[query_match.pattern_index],
+            captured_node.node.range(),
+          ),
+          query_match.captures.iter().cloned().collect_vec(),
+        );
+      }
+    }
+
+    for ((rule_index, captured_node_range), query_matches) in query_matches_by_rule_and_range {
+      let outermost_range = captured_node_range.start_byte..captured_node_range.end_byte;
+      // This ensures that each pattern of the rule's query matches the same node.
+      if query_matches.len() != combined_query.pattern_count_by_rule[rule_index]
+        || self.matches_by_rule[rule_index]
+          .iter()
+          .any(|(r, _)| r == &outermost_range)
+      {
+        continue;
+      }
+      let p_match = get_match_for_query_matches(
+        &combined_query.query,
+        query_matches,
+        captured_node_range,
+        source_code,
+        &combined_query.replace_node_by_rule[rule_index],
+        combined_query.reindent,
+        &combined_query.tag_names_by_rule[rule_index],
+      );
+      self.matches_by_rule[rule_index].push((outermost_range, p_match));
+    }
+    // This sorts the matches from bottom to top
+    for rule_matches in &mut self.matches_by_rule {
+      rule_matches.sort_by_key(|(_, p_match)| p_match.range().start_byte);
+      rule_matches.reverse();
+    }
+  }
+}
+
+// Gets the match `p_match` shifted by `delta` bytes, if the (updated) tree has a node at the shifted range.
+fn shift_match(p_match: &Match, root_node: Node, delta: isize) -> Option<Match> {
+  let start_byte = (p_match.range().start_byte as isize + delta) as usize;
+  let end_byte = (p_match.range().end_byte as isize + delta) as usize;
+  let node = get_node_for_range(root_node, start_byte, end_byte);
+  (node.start_byte() == start_byte && node.end_byte() == end_byte)
+    .then(|| Match::new(node.range(), p_match.matches().clone()))
+}
+
+#[cfg(test)]
+#[path = "unit_tests/combined_query_test.rs"]
+mod combined_query_test;
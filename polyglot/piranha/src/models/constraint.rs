@@ -20,7 +20,7 @@ use crate::utilities::tree_sitter_utilities::{
   get_node_for_range, substitute_tags, PiranhaHelpers,
 };
 
-use super::{rule_store::RuleStore, source_code_unit::SourceCodeUnit};
+use super::{piranha_error::PiranhaError, rule_store::RuleStore, source_code_unit::SourceCodeUnit};
 
 #[derive(Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct Constraint {
@@ -45,9 +45,9 @@ impl Constraint {
   /// i.e. finds scope for constraint.
   /// Within this scope it checks if the `constraint.query` DOES NOT MATCH any sub-tree.
   pub(crate) fn is_satisfied(
-    &self, node: Node, source_code_unit: SourceCodeUnit, rule_store: &mut RuleStore,
+    &self, node: Node, source_code_unit: &SourceCodeUnit, rule_store: &mut RuleStore,
     substitutions: &HashMap<String, String>,
-  ) -> bool {
+  ) -> Result<bool, PiranhaError> {
     let mut current_node = node;
     // This ensures that the below while loop considers the current node too when checking for constraints.
     // It does not make sense to check for constraint if current node is a "leaf" node.
@@ -57,9 +57,9 @@ impl Constraint {
     // Get the scope_node of the constraint (`scope.matcher`)
     let mut matched_matcher = false;
     while let Some(parent) = current_node.parent() {
-      let query_str = &self.matcher(substitutions);
+      let matcher_query = rule_store.query(&self.matcher(substitutions))?;
       if let Some(p_match) =
-        parent.get_match_for_query(&source_code_unit.code(), rule_store.query(query_str), false)
+        parent.get_match_for_query(source_code_unit.code(), &matcher_query, false)
       {
         matched_matcher = true;
         let scope_node = get_node_for_range(
@@ -69,20 +69,20 @@ impl Constraint {
         );
         for query_with_holes in self.queries() {
           let query_str = substitute_tags(query_with_holes.to_string(), substitutions, true);
-          let query = &rule_store.query(&query_str);
+          let query = &rule_store.query(&query_str)?;
           // If this query matches anywhere within the scope, return false.
           if scope_node
-            .get_match_for_query(&source_code_unit.code(), query, true)
+            .get_match_for_query(source_code_unit.code(), query, true)
             .is_some()
           {
-            return false;
+            return Ok(false);
           }
         }
         break;
       }
       current_node = parent;
     }
-    matched_matcher
+    Ok(matched_matcher)
   }
 }
 
@@ -0,0 +1,56 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use serde_derive::Serialize;
+
+use super::edit::Edit;
+use pyo3::prelude::pyclass;
+
+/// Captures an edit that caused the application of a rule to a source code unit to be rolled back
+/// (i.e. it produced syntactically incorrect code or exceeded the rewrite limits).
+#[derive(Serialize, Debug, Clone)]
+#[pyclass]
+pub struct Diagnostic {
+  // The rule that produced the failing edit
+  #[pyo3(get)]
+  rule_name: String,
+  // The edit that produced the failure
+  #[pyo3(get)]
+  edit: Edit,
+  // Description of the failure
+  #[pyo3(get)]
+  message: String,
+}
+
+impl Diagnostic {
+  pub(crate) fn new(rule_name: String, edit: Edit, message: String) -> Self {
+    Self {
+      rule_name,
+      edit,
+      message,
+    }
+  }
+
+  pub(crate) fn rule_name(&self) -> &str {
+    self.rule_name.as_ref()
+  }
+
+  #[cfg(test)]
+  pub(crate) fn edit(&self) -> &Edit {
+    &self.edit
+  }
+
+  pub(crate) fn message(&self) -> &str {
+    self.message.as_ref()
+  }
+}
@@ -0,0 +1,81 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use aho_corasick::AhoCorasick;
+use itertools::Itertools;
+use regex::Regex;
+
+use super::{piranha_error::PiranhaError, rule::Rule};
+
+/// A pre-filter that identifies the files that could potentially be matched by a set of (global) rules,
+/// based on the grep heuristics of these rules.
+///
+/// At the directory level, we would always look to perform global rules. However this is expensive because
+/// it requires parsing each file. To overcome this, we apply this simple
+/// heuristic to find the (upper bound) files that would match one of the rules.
+/// The literal heuristics are searched for simultaneously (Aho-Corasick), so that a large number of
+/// literals (e.g. thousands of stale flags) can be searched for efficiently.
+pub(crate) struct GrepHeuristics {
+  // Matches any of the literal grep heuristics
+  literals: AhoCorasick,
+  // The regex grep heuristics
+  regexes: Vec<Regex>,
+  // Some rule has no grep heuristics, i.e. it could match any file
+  match_all: bool,
+}
+
+impl GrepHeuristics {
+  pub(crate) fn new(rules: &[Rule]) -> Result<Self, PiranhaError> {
+    let match_all = rules
+      .iter()
+      .any(|r| r.grep_heuristics().is_empty() && r.grep_heuristics_regex().is_empty());
+
+    let literals = rules
+      .iter()
+      .flat_map(|r| r.grep_heuristics())
+      // An empty literal would match every file
+      .filter(|x| !x.is_empty())
+      .sorted()
+      //Remove duplicates
+      .dedup()
+      .collect_vec();
+
+    let mut regexes = vec![];
+    for rule in rules {
+      for pattern in rule.grep_heuristics_regex().iter().sorted() {
+        let regex = Regex::new(pattern).map_err(|e| PiranhaError::InvalidRule {
+          rule_name: rule.name(),
+          message: format!("Invalid grep heuristic {} : {}", pattern, e),
+        })?;
+        regexes.push(regex);
+      }
+    }
+
+    Ok(Self {
+      literals: AhoCorasick::new(literals),
+      regexes,
+      match_all,
+    })
+  }
+
+  /// Checks if the `content` (of a file) could be matched by one of the rules.
+  pub(crate) fn is_match(&self, content: &str) -> bool {
+    self.match_all
+      || self.literals.is_match(content)
+      || self.regexes.iter().any(|r| r.is_match(content))
+  }
+}
+
+#[cfg(test)]
+#[path = "unit_tests/grep_heuristics_test.rs"]
+mod grep_heuristics_test;
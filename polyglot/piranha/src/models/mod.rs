@@ -11,12 +11,16 @@ Copyright (c) 2022 Uber Technologies, Inc.
  limitations under the License.
 */
 
+pub(crate) mod combined_query;
 pub(crate) mod constraint;
+pub(crate) mod diagnostic;
 pub(crate) mod edit;
+pub(crate) mod grep_heuristics;
 pub(crate) mod matches;
 pub(crate) mod outgoing_edges;
 pub mod piranha_arguments;
 pub(crate) mod piranha_config;
+pub mod piranha_error;
 pub mod piranha_output;
 pub(crate) mod rule;
 pub(crate) mod rule_graph;
@@ -21,7 +21,7 @@ use tree_sitter::Language;
 
 use crate::{
   config::CommandLineArguments,
-  models::piranha_config::PiranhaConfiguration,
+  models::{piranha_config::PiranhaConfiguration, piranha_error::PiranhaError},
   utilities::{read_toml, tree_sitter_utilities::TreeSitterHelpers},
 };
 
@@ -46,7 +46,7 @@ pub struct PiranhaArguments {
   /// Tree-sitter language model
   #[getset(get_copy = "pub")]
   language: Language,
-  // The language name is file the extension used for files in particular language.
+  // The language name (e.g. `java`). It determines the extension(s) of the files to analyze.
   #[getset(get = "pub")]
   language_name: String,
   // User option that determines whether an empty file will be deleted
@@ -71,19 +71,44 @@ pub struct PiranhaArguments {
   /// The AST Kinds for which comments should be deleted
   #[getset(get = "pub")]
   cleanup_comments: bool,
+  /// The maximum number of rewrites a single application of a rule can perform on a file.
+  /// Exceeding this limit (e.g. because of a cycle in the rule graph) rolls back the application of the rule.
+  #[getset(get_copy = "pub")]
+  max_rewrites_per_rule: usize,
+  /// The maximum number of rewrites that can be performed on a file.
+  #[getset(get_copy = "pub")]
+  max_rewrites_per_file: usize,
+  /// The maximum time (in seconds) spent applying the rules to a single file.
+  /// A file exceeding it is left untouched (and reported as skipped), i.e. all its updates are discarded.
+  #[getset(get_copy = "pub")]
+  file_timeout: Option<u64>,
+  /// The maximum time (in seconds) for the whole run. Once exceeded, the files still being processed (or to be
+  /// processed) are left untouched and skipped, while the updates of the other files are still reported.
+  #[getset(get_copy = "pub")]
+  global_timeout: Option<u64>,
+  /// The maximum size (in bytes) of the files Piranha processes. Larger files are skipped.
+  #[getset(get_copy = "pub")]
+  max_file_size: Option<usize>,
+  /// Glob patterns (relative to the code base) of the files to process. If empty, all the files are processed.
+  #[getset(get = "pub")]
+  include: Vec<String>,
+  /// Glob patterns (relative to the code base) of the files to never process (e.g. generated or vendored code).
+  /// Note that the files ignored by `.gitignore` or `.ignore` files are never processed either.
+  #[getset(get = "pub")]
+  exclude: Vec<String>,
 }
 
 impl PiranhaArguments {
-  pub fn from_command_line() -> Self {
+  pub fn from_command_line() -> Result<Self, PiranhaError> {
     Self::new(CommandLineArguments::parse())
   }
 
-  pub(crate) fn new(args: CommandLineArguments) -> Self {
+  pub(crate) fn new(args: CommandLineArguments) -> Result<Self, PiranhaError> {
     let path_to_piranha_argument_file =
       PathBuf::from(args.path_to_configurations.as_str()).join("piranha_arguments.toml");
 
     let piranha_args_from_config: PiranhaConfiguration =
-      read_toml(&path_to_piranha_argument_file, false);
+      read_toml(&path_to_piranha_argument_file, false)?;
 
     let input_substitutions = piranha_args_from_config.substitutions();
 
@@ -98,7 +123,7 @@ impl PiranhaArguments {
       .path_to_configurations(args.path_to_configurations)
       .path_to_output_summaries(args.path_to_output_summary)
       .language_name(piranha_args_from_config.language())
-      .language(piranha_args_from_config.language().get_language());
+      .language(piranha_args_from_config.language().get_language()?);
 
     if let Some(v) = piranha_args_from_config.delete_file_if_empty() {
       args_builder.delete_file_if_empty(v);
@@ -118,20 +143,35 @@ impl PiranhaArguments {
       args_builder.cleanup_comments(ast_kinds);
     }
 
-    args_builder.build().unwrap()
+    if let Some(limit) = piranha_args_from_config.max_rewrites_per_rule() {
+      args_builder.max_rewrites_per_rule(limit);
+    }
+
+    if let Some(limit) = piranha_args_from_config.max_rewrites_per_file() {
+      args_builder.max_rewrites_per_file(limit);
+    }
+
+    args_builder
+      .file_timeout(piranha_args_from_config.file_timeout())
+      .global_timeout(piranha_args_from_config.global_timeout())
+      .max_file_size(piranha_args_from_config.max_file_size())
+      .include([piranha_args_from_config.include(), args.include].concat())
+      .exclude([piranha_args_from_config.exclude(), args.exclude].concat());
+
+    // All the fields have default values, hence building the arguments cannot fail.
+    Ok(args_builder.build().unwrap())
   }
 }
 
 impl Default for PiranhaArguments {
   fn default() -> Self {
-    let language_name = String::from("java");
     PiranhaArguments {
       path_to_code_base: String::new(),
       input_substitutions: HashMap::new(),
       path_to_configurations: String::new(),
       path_to_output_summaries: None,
-      language: language_name.get_language(),
-      language_name,
+      language: tree_sitter_java::language(),
+      language_name: String::from("java"),
       delete_consecutive_new_lines: false,
       delete_file_if_empty: true,
       /// default Global prefix tag us "GLOBAL_TAG."
@@ -141,6 +181,13 @@ impl Default for PiranhaArguments {
       number_of_ancestors_in_parent_scope: 4,
       cleanup_comments_buffer: 2,
       cleanup_comments: false,
+      max_rewrites_per_rule: 1000,
+      max_rewrites_per_file: 10000,
+      file_timeout: None,
+      global_timeout: None,
+      max_file_size: None,
+      include: vec![],
+      exclude: vec![],
     }
   }
 }
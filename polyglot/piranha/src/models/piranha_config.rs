@@ -23,6 +23,13 @@ pub(crate) struct PiranhaConfiguration {
   global_tag_prefix: Option<String>,
   cleanup_comments_buffer: Option<usize>,
   cleanup_comments: Option<bool>,
+  max_rewrites_per_rule: Option<usize>,
+  max_rewrites_per_file: Option<usize>,
+  file_timeout: Option<u64>,
+  global_timeout: Option<u64>,
+  max_file_size: Option<usize>,
+  include: Option<Vec<String>>,
+  exclude: Option<Vec<String>>,
 }
 
 impl PiranhaConfiguration {
@@ -57,4 +64,32 @@ impl PiranhaConfiguration {
   pub(crate) fn cleanup_comments(&self) -> Option<bool> {
     self.cleanup_comments
   }
+
+  pub(crate) fn max_rewrites_per_rule(&self) -> Option<usize> {
+    self.max_rewrites_per_rule
+  }
+
+  pub(crate) fn max_rewrites_per_file(&self) -> Option<usize> {
+    self.max_rewrites_per_file
+  }
+
+  pub(crate) fn file_timeout(&self) -> Option<u64> {
+    self.file_timeout
+  }
+
+  pub(crate) fn global_timeout(&self) -> Option<u64> {
+    self.global_timeout
+  }
+
+  pub(crate) fn max_file_size(&self) -> Option<usize> {
+    self.max_file_size
+  }
+
+  pub(crate) fn include(&self) -> Vec<String> {
+    self.include.clone().unwrap_or_default()
+  }
+
+  pub(crate) fn exclude(&self) -> Vec<String> {
+    self.exclude.clone().unwrap_or_default()
+  }
 }
@@ -0,0 +1,101 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use std::fmt;
+
+use pyo3::{
+  exceptions::{PyIOError, PyRuntimeError, PyValueError},
+  PyErr,
+};
+
+use super::diagnostic::Diagnostic;
+
+/// Captures the errors that can occur when configuring or running Piranha.
+#[derive(Debug)]
+pub enum PiranhaError {
+  /// A (configuration) file could not be read.
+  ReadFile { path: String, message: String },
+  /// A `toml` configuration could not be deserialized.
+  ParseToml { path: String, message: String },
+  /// The language is not supported by Piranha.
+  UnsupportedLanguage(String),
+  /// A glob pattern (`include` or `exclude`) could not be parsed.
+  InvalidGlob { glob: String, message: String },
+  /// A tree-sitter query could not be compiled.
+  InvalidQuery { query: String, message: String },
+  /// A rule is malformed or could not be instantiated.
+  InvalidRule { rule_name: String, message: String },
+  /// No enclosing scope (as defined in `scope_config.toml`) could be found for the previous edit.
+  /// Like `SyntacticallyIncorrectRewrite`, Piranha recovers from this error by rolling back the application of the rule.
+  ScopeNotFound(Box<Diagnostic>),
+  /// An edit produced syntactically incorrect code.
+  /// Piranha recovers from this error by rolling back the application of the rule (it is never returned by `execute_piranha`).
+  SyntacticallyIncorrectRewrite(Box<Diagnostic>),
+  /// A rule (or the rules applied to a file) exceeded the configured number of rewrites, which usually indicates that
+  /// the rule application does not terminate.
+  /// Like `SyntacticallyIncorrectRewrite`, Piranha recovers from this error by rolling back the application of the rule.
+  RewriteLimitExceeded(Box<Diagnostic>),
+  /// Applying the rules to a file exceeded the time budget (i.e. `file_timeout` or `global_timeout`).
+  /// Piranha recovers from this error by skipping the file (it is never returned by `execute_piranha`).
+  TimeoutExceeded(String),
+}
+
+impl fmt::Display for PiranhaError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PiranhaError::ReadFile { path, message } => {
+        write!(f, "Could not read file {} : {}", path, message)
+      }
+      PiranhaError::ParseToml { path, message } => {
+        write!(f, "Could not parse {} : {}", path, message)
+      }
+      PiranhaError::UnsupportedLanguage(language) => {
+        write!(f, "Language not supported : {}", language)
+      }
+      PiranhaError::InvalidGlob { glob, message } => {
+        write!(f, "Could not parse the glob pattern {} : {}", glob, message)
+      }
+      PiranhaError::InvalidQuery { query, message } => {
+        write!(f, "Could not parse the query : {} \n {}", query, message)
+      }
+      PiranhaError::InvalidRule { rule_name, message } => {
+        write!(f, "Invalid rule {} : {}", rule_name, message)
+      }
+      PiranhaError::ScopeNotFound(diagnostic) => write!(f, "{}", diagnostic.message()),
+      PiranhaError::SyntacticallyIncorrectRewrite(diagnostic) => write!(
+        f,
+        "Rule {} produced syntactically incorrect code",
+        diagnostic.rule_name()
+      ),
+      PiranhaError::RewriteLimitExceeded(diagnostic) => write!(f, "{}", diagnostic.message()),
+      PiranhaError::TimeoutExceeded(reason) => write!(f, "Timeout exceeded : {}", reason),
+    }
+  }
+}
+
+impl std::error::Error for PiranhaError {}
+
+/// Maps Piranha's errors to Python exceptions (for the Python API).
+impl From<PiranhaError> for PyErr {
+  fn from(error: PiranhaError) -> PyErr {
+    let message = error.to_string();
+    match error {
+      PiranhaError::ReadFile { .. } => PyIOError::new_err(message),
+      PiranhaError::SyntacticallyIncorrectRewrite(_)
+      | PiranhaError::RewriteLimitExceeded(_)
+      | PiranhaError::ScopeNotFound(_)
+      | PiranhaError::TimeoutExceeded(_) => PyRuntimeError::new_err(message),
+      _ => PyValueError::new_err(message),
+    }
+  }
+}
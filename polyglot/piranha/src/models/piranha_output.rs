@@ -11,12 +11,12 @@ Copyright (c) 2022 Uber Technologies, Inc.
  limitations under the License.
 */
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use itertools::Itertools;
 use serde_derive::Serialize;
 
-use super::{edit::Edit, matches::Match, source_code_unit::SourceCodeUnit};
+use super::{diagnostic::Diagnostic, edit::Edit, matches::Match, source_code_unit::SourceCodeUnit};
 use pyo3::prelude::pyclass;
 #[derive(Serialize, Debug, Clone, Default)]
 #[pyclass]
@@ -29,18 +29,35 @@ pub struct PiranhaOutputSummary {
   matches: Vec<(String, Match)>,
   #[pyo3(get)]
   rewrites: Vec<Edit>,
+  #[pyo3(get)]
+  diagnostics: Vec<Diagnostic>,
+  // The reason why the file was skipped (if it was), in which case the file is left untouched
+  #[pyo3(get)]
+  skipped: Option<String>,
 }
 
 impl PiranhaOutputSummary {
   pub(crate) fn new(source_code_unit: &SourceCodeUnit) -> PiranhaOutputSummary {
     return PiranhaOutputSummary {
       path: String::from(source_code_unit.path().as_os_str().to_str().unwrap()),
-      content: source_code_unit.code(),
+      content: source_code_unit.code().to_string(),
       matches: source_code_unit.matches().iter().cloned().collect_vec(),
       rewrites: source_code_unit.rewrites().iter().cloned().collect_vec(),
+      diagnostics: source_code_unit.diagnostics().iter().cloned().collect_vec(),
+      skipped: None,
     };
   }
 
+  /// Creates the summary of a file that was skipped (for the given `reason`).
+  /// Since the file is left untouched, its content is not reported.
+  pub(crate) fn new_skipped(path: &Path, reason: String) -> PiranhaOutputSummary {
+    PiranhaOutputSummary {
+      path: String::from(path.as_os_str().to_str().unwrap()),
+      skipped: Some(format!("skipped: {}", reason)),
+      ..Default::default()
+    }
+  }
+
   pub(crate) fn matches(&self) -> &[(String, Match)] {
     self.matches.as_ref()
   }
@@ -49,6 +66,14 @@ impl PiranhaOutputSummary {
     self.rewrites.as_ref()
   }
 
+  pub(crate) fn diagnostics(&self) -> &[Diagnostic] {
+    self.diagnostics.as_ref()
+  }
+
+  pub fn skipped(&self) -> Option<&String> {
+    self.skipped.as_ref()
+  }
+
   pub fn path(&self) -> PathBuf {
     PathBuf::from(self.path.as_str())
   }
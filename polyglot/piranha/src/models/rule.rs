@@ -19,13 +19,15 @@ use serde_derive::Deserialize;
 use tree_sitter::Node;
 
 use crate::utilities::{
-  tree_sitter_utilities::{get_context, get_node_for_range, substitute_tags, PiranhaHelpers},
+  tree_sitter_utilities::{
+    get_context, get_node_for_range, substitute_tags, PiranhaHelpers, TreeSitterHelpers,
+  },
   MapOfVec,
 };
 
 use super::{
-  constraint::Constraint, edit::Edit, matches::Match, rule_store::RuleStore,
-  source_code_unit::SourceCodeUnit,
+  constraint::Constraint, edit::Edit, matches::Match, piranha_error::PiranhaError,
+  rule_store::RuleStore, source_code_unit::SourceCodeUnit,
 };
 
 static SEED: &str = "Seed Rule";
@@ -54,7 +56,14 @@ pub(crate) struct Rule {
   /// Additional constraints for matching the rule
   constraints: Option<HashSet<Constraint>>,
   /// Heuristics for identifying potential files containing occurrence of the rule.
+  /// These are literal strings (that can contain holes). Only the files containing one of these strings are analyzed.
+  /// If neither `grep_heuristics` nor `grep_heuristics_regex` is provided, the values substituted for the holes are used.
   grep_heuristics: Option<HashSet<String>>,
+  /// Same as `grep_heuristics`, but these are regular expressions (that can contain holes).
+  /// The values substituted for the holes are regex-escaped.
+  grep_heuristics_regex: Option<HashSet<String>>,
+  /// Holes whose values should not be used as grep heuristics (e.g. `treated`, whose value `true` occurs in most files)
+  grep_heuristics_excluded_holes: Option<HashSet<String>>,
 }
 
 impl Rule {
@@ -72,20 +81,11 @@ impl Rule {
     self.query.is_some() && self.replace.is_none()
   }
 
-  /// Instantiate `self` with substitutions or panic.
-  pub(crate) fn instantiate(&self, substitutions: &HashMap<String, String>) -> Rule {
-    if let Ok(r) = self.try_instantiate(substitutions) {
-      return r;
-    }
-    #[rustfmt::skip]
-      panic!("{}", format!("Could not instantiate the rule {:?} with substitutions {:?}", self, substitutions).red());
-  }
-
   /// Tries to instantiate the rule (`self`) based on the substitutions.
   /// Note this could fail if the `substitutions` doesn't contain mappings for each hole.
-  pub(crate) fn try_instantiate(
+  pub(crate) fn instantiate(
     &self, substitutions: &HashMap<String, String>,
-  ) -> Result<Rule, String> {
+  ) -> Result<Rule, PiranhaError> {
     let relevant_substitutions = self
       .holes()
       .iter()
@@ -96,17 +96,18 @@ impl Rule {
   }
 
   /// Create a new query from `self` by updating the `query` and `replace` based on the substitutions.
-  fn update(&self, substitutions: &HashMap<String, String>) -> Result<Rule, String> {
+  fn update(&self, substitutions: &HashMap<String, String>) -> Result<Rule, PiranhaError> {
     if substitutions.len() != self.holes().len() {
       #[rustfmt::skip]
-        return Err(format!("Could not instantiate a rule - {:?}. Some Holes {:?} not found in table {:?}", self, self.holes(), substitutions));
+      return Err(self.invalid(format!("Some Holes {:?} not found in table {:?}", self.holes(), substitutions)));
     } else {
       let mut updated_rule = self.clone();
       if !updated_rule.holes().is_empty() {
-        updated_rule.update_query(substitutions);
+        updated_rule.update_query(substitutions)?;
         if !updated_rule.is_match_only_rule() {
-          updated_rule.update_replace(substitutions);
+          updated_rule.update_replace(substitutions)?;
         }
+        updated_rule.update_grep_heuristics(substitutions);
       }
       Ok(updated_rule)
     }
@@ -128,18 +129,27 @@ impl Rule {
     (rules_by_name, rules_by_group)
   }
 
-  /// Records the string that should be grepped in order to find files that
-  /// potentially could match this global rule.
+  /// Records the strings that should be grepped in order to find files that
+  /// potentially could match this global rule (unless the rule declares its grep heuristics).
+  /// These are the values substituted for the holes (except `grep_heuristics_excluded_holes`), ignoring
+  /// the empty values.
   pub(crate) fn add_grep_heuristics_for_global_rules(
     &mut self, substitutions: &HashMap<String, String>,
   ) {
+    if self.grep_heuristics.is_some() || self.grep_heuristics_regex.is_some() {
+      return;
+    }
+    let excluded_holes = self.grep_heuristics_excluded_holes();
     let mut gh = HashSet::new();
-    for hole in self.holes() {
-      if let Some(x) = substitutions.get(&hole) {
-        gh.insert(x.clone());
+    for hole in self.holes().difference(&excluded_holes) {
+      if let Some(x) = substitutions.get(hole) {
+        // An empty value would match every file
+        if !x.is_empty() {
+          gh.insert(x.clone());
+        }
       }
     }
-    self.grep_heuristics = Some(gh.clone());
+    self.grep_heuristics = Some(gh);
   }
 
   /// Adds the rule to a new group - "SEED" if applicable.
@@ -155,25 +165,32 @@ impl Rule {
     }
   }
 
-  pub(crate) fn replace_node(&self) -> String {
-    if let Some(rn) = &self.replace_node {
-      return rn.to_string();
-    }
-    panic!("No replace_node pattern!")
+  pub(crate) fn replace_node(&self) -> Result<String, PiranhaError> {
+    self
+      .replace_node
+      .clone()
+      .ok_or_else(|| self.invalid("No replace_node pattern!".to_string()))
   }
 
-  pub(crate) fn query(&self) -> String {
-    if let Some(q) = &self.query {
-      return q.to_string();
-    }
-    panic!("No query pattern!")
+  pub(crate) fn query(&self) -> Result<String, PiranhaError> {
+    self
+      .query
+      .clone()
+      .ok_or_else(|| self.invalid("No query pattern!".to_string()))
+  }
+
+  pub(crate) fn replace(&self) -> Result<String, PiranhaError> {
+    self
+      .replace
+      .clone()
+      .ok_or_else(|| self.invalid("No replace pattern!".to_string()))
   }
 
-  pub(crate) fn replace(&self) -> String {
-    if let Some(rp) = &self.replace {
-      return rp.to_string();
+  fn invalid(&self, message: String) -> PiranhaError {
+    PiranhaError::InvalidRule {
+      rule_name: self.name(),
+      message,
     }
-    panic!("No replace pattern!")
   }
 
   pub(crate) fn constraints(&self) -> HashSet<Constraint> {
@@ -190,6 +207,20 @@ impl Rule {
     }
   }
 
+  pub(crate) fn grep_heuristics_regex(&self) -> HashSet<String> {
+    match &self.grep_heuristics_regex {
+      Some(cs) => cs.clone(),
+      None => HashSet::new(),
+    }
+  }
+
+  fn grep_heuristics_excluded_holes(&self) -> HashSet<String> {
+    match &self.grep_heuristics_excluded_holes {
+      Some(cs) => cs.clone(),
+      None => HashSet::new(),
+    }
+  }
+
   pub(crate) fn holes(&self) -> HashSet<String> {
     match &self.holes {
       Some(cs) => cs.clone(),
@@ -204,12 +235,43 @@ impl Rule {
     }
   }
 
-  pub(crate) fn update_replace(&mut self, substitutions: &HashMap<String, String>) {
-    self.set_replace(substitute_tags(self.replace(), substitutions, false));
+  pub(crate) fn update_replace(
+    &mut self, substitutions: &HashMap<String, String>,
+  ) -> Result<(), PiranhaError> {
+    self.set_replace(substitute_tags(self.replace()?, substitutions, false));
+    Ok(())
   }
 
-  pub(crate) fn update_query(&mut self, substitutions: &HashMap<String, String>) {
-    self.set_query(substitute_tags(self.query(), substitutions, false));
+  pub(crate) fn update_query(
+    &mut self, substitutions: &HashMap<String, String>,
+  ) -> Result<(), PiranhaError> {
+    self.set_query(substitute_tags(self.query()?, substitutions, false));
+    Ok(())
+  }
+
+  /// Substitutes the holes in the (declared) grep heuristics.
+  /// Note that the values substituted in the regex grep heuristics are regex-escaped.
+  fn update_grep_heuristics(&mut self, substitutions: &HashMap<String, String>) {
+    let escaped_substitutions = substitutions
+      .iter()
+      .map(|(k, v)| (k.to_string(), regex::escape(v)))
+      .collect();
+    let substitute = |heuristics: &HashSet<String>, substitutions: &HashMap<String, String>| {
+      heuristics
+        .iter()
+        .map(|x| substitute_tags(x.to_string(), substitutions, false))
+        // A hole substituted with "" would produce a heuristic matching every file
+        .filter(|x| !x.is_empty())
+        .collect()
+    };
+    self.grep_heuristics = self
+      .grep_heuristics
+      .as_ref()
+      .map(|gh| substitute(gh, substitutions));
+    self.grep_heuristics_regex = self
+      .grep_heuristics_regex
+      .as_ref()
+      .map(|gh| substitute(gh, &escaped_substitutions));
   }
 
   pub(crate) fn name(&self) -> String {
@@ -221,7 +283,7 @@ impl Rule {
   pub(crate) fn get_edit_for_context(
     source_code_unit: &SourceCodeUnit, previous_edit_start: usize, previous_edit_end: usize,
     rules_store: &mut RuleStore, rules: &Vec<Rule>,
-  ) -> Option<Edit> {
+  ) -> Result<Option<Edit>, PiranhaError> {
     let number_of_ancestors_in_parent_scope =
       *rules_store.get_number_of_ancestors_in_parent_scope();
     let changed_node = get_node_for_range(
@@ -234,82 +296,89 @@ impl Rule {
       format!("Changed node kind {}", changed_node.kind()).blue()
     );
     // Context contains -  the changed node in the previous edit, its's parent, grand parent and great grand parent
-    let context = || {
-      get_context(
-        source_code_unit.root_node(),
-        changed_node,
-        source_code_unit.code(),
-        number_of_ancestors_in_parent_scope,
-      )
-    };
+    let context = get_context(
+      source_code_unit.root_node(),
+      changed_node,
+      source_code_unit.code(),
+      number_of_ancestors_in_parent_scope,
+    );
     for rule in rules {
-      for ancestor in &context() {
-        if let Some(edit) = rule.get_edit(&source_code_unit.clone(), rules_store, *ancestor, false)
-        {
-          return Some(edit);
+      for ancestor in &context {
+        if let Some(edit) = rule.get_edit(source_code_unit, rules_store, *ancestor, false)? {
+          return Ok(Some(edit));
         }
       }
     }
-    None
+    Ok(None)
   }
 
   /// Gets the first match for the rule in `self`
   pub(crate) fn get_matches(
     &self, source_code_unit: &SourceCodeUnit, rule_store: &mut RuleStore, node: Node,
     recursive: bool,
-  ) -> Vec<Match> {
+  ) -> Result<Vec<Match>, PiranhaError> {
     let mut output: Vec<Match> = vec![];
     // Get all matches for the query in the given scope `node`.
     let replace_node_tag = if self.is_match_only_rule() || self.is_dummy_rule() {
       None
     } else {
-      Some(self.replace_node())
+      Some(self.replace_node()?)
     };
+    let query = rule_store.query(&self.query()?)?;
+    let reindent = rule_store
+      .language_name()
+      .to_string()
+      .is_indentation_sensitive();
     let all_query_matches = node.get_all_matches_for_query(
       source_code_unit.code(),
-      rule_store.query(&self.query()),
+      &query,
       recursive,
       replace_node_tag,
+      reindent,
     );
 
     // Return the first match that satisfies constraint of the rule
     for p_match in all_query_matches {
-      let matched_node = get_node_for_range(
-        source_code_unit.root_node(),
-        p_match.range().start_byte,
-        p_match.range().end_byte,
-      );
-
-      if matched_node.satisfies_constraint(
-        source_code_unit.clone(),
-        self,
-        p_match.matches(),
-        rule_store,
-      ) {
+      if self.is_satisfied_by(source_code_unit, rule_store, &p_match)? {
         trace!("Found match {:#?}", p_match);
         output.push(p_match);
       }
     }
     debug!("Matches found {}", output.len());
-    output
+    Ok(output)
+  }
+
+  /// Checks if the match `p_match` (of the rule's query) satisfies the constraints of the rule.
+  pub(crate) fn is_satisfied_by(
+    &self, source_code_unit: &SourceCodeUnit, rule_store: &mut RuleStore, p_match: &Match,
+  ) -> Result<bool, PiranhaError> {
+    let matched_node = get_node_for_range(
+      source_code_unit.root_node(),
+      p_match.range().start_byte,
+      p_match.range().end_byte,
+    );
+    matched_node.satisfies_constraint(source_code_unit, self, p_match.matches(), rule_store)
   }
 
   /// Gets the first match for the rule in `self`
   pub(crate) fn get_edit(
     &self, source_code_unit: &SourceCodeUnit, rule_store: &mut RuleStore, node: Node,
     recursive: bool,
-  ) -> Option<Edit> {
+  ) -> Result<Option<Edit>, PiranhaError> {
     // Get all matches for the query in the given scope `node`.
+    let matches = self.get_matches(source_code_unit, rule_store, node, recursive)?;
+    if let Some(p_match) = matches.first() {
+      return Ok(Some(self.get_edit_for_match(p_match)?));
+    }
+    Ok(None)
+  }
 
-    return self
-      .get_matches(source_code_unit, rule_store, node, recursive)
-      .first()
-      .map(|p_match| {
-        let replacement = substitute_tags(self.replace(), p_match.matches(), false);
-        let edit = Edit::new(p_match.clone(), replacement, self.name());
-        trace!("Rewrite found : {:#?}", edit);
-        edit
-      });
+  /// Gets the edit replacing the match `p_match` (of the rule's query) with the rule's replacement.
+  pub(crate) fn get_edit_for_match(&self, p_match: &Match) -> Result<Edit, PiranhaError> {
+    let replacement = substitute_tags(self.replace()?, p_match.matches(), false);
+    let edit = Edit::new(p_match.clone(), replacement, self.name());
+    trace!("Rewrite found : {:#?}", edit);
+    Ok(edit)
   }
 
   pub(crate) fn set_replace(&mut self, replace: String) {
@@ -340,6 +409,8 @@ impl Rule {
         Some(constraints)
       },
       grep_heuristics: None,
+      grep_heuristics_regex: None,
+      grep_heuristics_excluded_holes: None,
     }
   }
 }
@@ -15,9 +15,10 @@ use crate::{
   models::{outgoing_edges::OutgoingEdges, rule::Rule},
   utilities::MapOfVec,
 };
-use std::collections::HashMap;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RuleGraph(HashMap<String, Vec<(String, String)>>);
 
 impl RuleGraph {
@@ -62,6 +63,46 @@ impl RuleGraph {
     self.0.get(rule_name).cloned().unwrap_or_default()
   }
 
+  /// Get the shortest cycle (i.e. path from `rule_name` back to itself) in the rule graph containing `rule_name`, if any.
+  pub(crate) fn get_cycle(&self, rule_name: &String) -> Option<Vec<String>> {
+    // Breadth first search from `rule_name`, recording the predecessor of each visited rule
+    let mut predecessors: HashMap<String, String> = HashMap::new();
+    let mut queue = VecDeque::from([rule_name.to_string()]);
+    while let Some(current) = queue.pop_front() {
+      for (_, next) in self.get_neighbors(&current) {
+        if next.eq(rule_name) {
+          // Walk back from `current` to `rule_name`
+          let mut cycle = vec![rule_name.to_string(), current.to_string()];
+          while let Some(predecessor) = predecessors.get(cycle.last().unwrap()) {
+            cycle.push(predecessor.to_string());
+          }
+          cycle.reverse();
+          return Some(cycle);
+        }
+        if !predecessors.contains_key(&next) {
+          predecessors.insert(next.to_string(), current.to_string());
+          queue.push_back(next);
+        }
+      }
+    }
+    None
+  }
+
+  /// Detects the cycles in the rule graph.
+  /// Returns the shortest cycle through each rule that is a part of some cycle (skipping the cycles visiting the same rules).
+  pub(crate) fn get_cycles(&self) -> Vec<Vec<String>> {
+    let mut cycles = vec![];
+    let mut visited_rules = HashSet::new();
+    for rule_name in self.0.keys().sorted() {
+      if let Some(cycle) = self.get_cycle(rule_name) {
+        if visited_rules.insert(cycle.iter().sorted().dedup().join(",")) {
+          cycles.push(cycle);
+        }
+      }
+    }
+    cycles
+  }
+
   /// Get the number of nodes and edges in the rule graph
   pub(crate) fn get_number_of_rules_and_edges(&self) -> (usize, usize) {
     let mut edges = 0;
@@ -73,8 +114,5 @@ impl RuleGraph {
 }
 
 #[cfg(test)]
-impl RuleGraph {
-  pub(crate) fn dummy() -> Self {
-    RuleGraph(HashMap::new())
-  }
-}
+#[path = "unit_tests/rule_graph_test.rs"]
+mod rule_graph_test;
@@ -11,16 +11,21 @@ Copyright (c) 2022 Uber Technologies, Inc.
  limitations under the License.
 */
 
-use std::collections::HashMap;
+use std::{
+  collections::HashMap,
+  sync::{Arc, RwLock},
+  time::Instant,
+};
 
 use colored::Colorize;
 use log::{debug, info, trace};
-use tree_sitter::{Language, Query};
+use tree_sitter::{Language, Parser, Query};
 
 use crate::{
   config::read_config_files,
   models::piranha_arguments::PiranhaArguments,
   models::{
+    piranha_error::PiranhaError,
     rule::Rule,
     rule_graph::RuleGraph,
     scopes::{ScopeGenerator, ScopeQueryGenerator},
@@ -28,72 +33,140 @@ use crate::{
   utilities::{tree_sitter_utilities::TreeSitterHelpers, MapOfVec},
 };
 
+#[cfg(test)]
+use crate::models::outgoing_edges::OutgoingEdges;
+
 pub(crate) static GLOBAL: &str = "Global";
 pub(crate) static PARENT: &str = "Parent";
 /// This maintains the state for Piranha.
-#[derive(Debug)]
+/// Note that the clones of a rule store share its immutable part (i.e. the rule graph, the scopes, the arguments and
+/// the query cache), so that the files can be processed in parallel, each with its own (cheap) copy of the rule store.
+#[derive(Debug, Clone)]
 pub(crate) struct RuleStore {
+  // The immutable part of the rule store (shared across threads).
+  shared: Arc<SharedRuleStore>,
+  // Current global rules to be applied.
+  global_rules: Vec<Rule>,
+  // Command line arguments passed to piranha
+  global_tags: HashMap<String, String>,
+  // The time by which the application of the rules (to the current file) has to complete, along with
+  // the description of the corresponding budget
+  deadline: Option<(Instant, String)>,
+}
+
+// The part of the rule store that does not change once the configuration is loaded.
+#[derive(Debug)]
+struct SharedRuleStore {
   // A graph that captures the flow amongst the rules
   rule_graph: RuleGraph,
   // Caches the compiled tree-sitter queries.
-  rule_query_cache: HashMap<String, Query>,
+  rule_query_cache: RwLock<HashMap<String, Arc<Query>>>,
   // All the input rules stored by name
   rules_by_name: HashMap<String, Rule>,
-  // Current global rules to be applied.
-  global_rules: Vec<Rule>,
   // Scope generators.
   scopes: Vec<ScopeGenerator>,
   // Command line arguments passed to piranha
   piranha_args: PiranhaArguments,
-  // Command line arguments passed to piranha
-  global_tags: HashMap<String, String>,
+}
+
+impl SharedRuleStore {
+  fn new(
+    rule_graph: RuleGraph, rules_by_name: HashMap<String, Rule>, scopes: Vec<ScopeGenerator>,
+    piranha_args: PiranhaArguments,
+  ) -> Arc<Self> {
+    Arc::new(SharedRuleStore {
+      rule_graph,
+      rule_query_cache: RwLock::default(),
+      rules_by_name,
+      scopes,
+      piranha_args,
+    })
+  }
 }
 
 impl RuleStore {
-  pub(crate) fn new(args: &PiranhaArguments) -> RuleStore {
-    let (rules, edges, scopes) = read_config_files(args);
+  pub(crate) fn new(args: &PiranhaArguments) -> Result<RuleStore, PiranhaError> {
+    let (rules, edges, scopes) = read_config_files(args)?;
     let rule_graph = RuleGraph::new(&edges, &rules);
+    let rules_by_name = rules.iter().map(|r| (r.name(), r.clone())).collect();
     let mut rule_store = RuleStore {
-      rule_graph,
-      rule_query_cache: HashMap::new(),
-      rules_by_name: rules.iter().map(|r| (r.name(), r.clone())).collect(),
+      shared: SharedRuleStore::new(rule_graph, rules_by_name, scopes, args.clone()),
       global_rules: vec![],
-      scopes,
-      piranha_args: args.clone(),
       global_tags: HashMap::new(),
+      deadline: None,
     };
 
-    for (_, rule) in rule_store.rules_by_name.clone() {
-      if rule.is_seed_rule() {
-        rule_store.add_to_global_rules(&rule, args.input_substitutions());
-      }
+    for rule in rules.iter().filter(|r| r.is_seed_rule()) {
+      rule_store.add_to_global_rules(rule, args.input_substitutions());
     }
+    let rule_graph = &rule_store.shared.rule_graph;
     info!(
       "Number of rules and edges loaded : {:?}",
-      rule_store.rule_graph.get_number_of_rules_and_edges()
+      rule_graph.get_number_of_rules_and_edges()
     );
+    for cycle in rule_graph.get_cycles() {
+      #[rustfmt::skip]
+      info!("{}", format!("Cycle in the rule graph : {}", cycle.join(" -> ")).yellow());
+    }
     trace!("Rule Store {}", format!("{:#?}", rule_store));
-    rule_store
+    Ok(rule_store)
+  }
+
+  pub(crate) fn set_deadline(&mut self, deadline: Option<(Instant, String)>) {
+    self.deadline = deadline;
+  }
+
+  /// Returns an error if the deadline for applying the rules (to the current file) has passed.
+  pub(crate) fn check_deadline(&self) -> Result<(), PiranhaError> {
+    match &self.deadline {
+      Some((deadline, budget)) if Instant::now() >= *deadline => {
+        Err(PiranhaError::TimeoutExceeded(budget.to_string()))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Limits the time the `parser` can spend on parsing to the time remaining until the deadline (if any).
+  pub(crate) fn set_parser_timeout(&self, parser: &mut Parser) {
+    // A timeout of 0 means no timeout
+    let timeout = self.deadline.as_ref().map_or(0, |(deadline, _)| {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      (remaining.as_micros() as u64).max(1)
+    });
+    parser.set_timeout_micros(timeout);
   }
 
   pub(crate) fn global_rules(&self) -> Vec<Rule> {
     self.global_rules.clone()
   }
 
+  pub(crate) fn max_rewrites_per_rule(&self) -> usize {
+    self.piranha_args().max_rewrites_per_rule()
+  }
+
+  pub(crate) fn max_rewrites_per_file(&self) -> usize {
+    self.piranha_args().max_rewrites_per_file()
+  }
+
+  /// Get the shortest cycle in the rule graph containing `rule_name` (if any).
+  pub(crate) fn get_cycle(&self, rule_name: &String) -> Option<Vec<String>> {
+    self.shared.rule_graph.get_cycle(rule_name)
+  }
+
   pub(crate) fn language(&self) -> Language {
-    self.piranha_args.language()
+    self.piranha_args().language()
   }
 
   pub(crate) fn language_name(&self) -> &str {
-    self.piranha_args.language_name()
+    self.piranha_args().language_name()
   }
 
   pub(crate) fn get_number_of_ancestors_in_parent_scope(&self) -> &u8 {
-    self.piranha_args.number_of_ancestors_in_parent_scope()
+    self.piranha_args().number_of_ancestors_in_parent_scope()
   }
 
   pub(crate) fn default_substitutions(&self) -> HashMap<String, String> {
-    let mut default_subs = self.piranha_args.input_substitutions().clone();
+    let mut default_subs = self.piranha_args().input_substitutions().clone();
     default_subs.extend(self.global_tags().clone());
     default_subs
   }
@@ -102,66 +175,94 @@ impl RuleStore {
   pub(crate) fn add_to_global_rules(
     &mut self, rule: &Rule, tag_captures: &HashMap<String, String>,
   ) {
-    if let Ok(mut r) = rule.try_instantiate(tag_captures) {
+    if let Ok(mut r) = rule.instantiate(tag_captures) {
       if !self.global_rules.iter().any(|r| {
-        r.name().eq(&rule.name()) && r.replace().eq(&rule.replace()) && r.query().eq(&rule.query())
+        r.name().eq(&rule.name())
+          && r.replace().ok().eq(&rule.replace().ok())
+          && r.query().ok().eq(&rule.query().ok())
       }) {
         r.add_grep_heuristics_for_global_rules(tag_captures);
         #[rustfmt::skip]
-        debug!("{}", format!("Added Global Rule : {:?} - {}", r.name(), r.query()).bright_blue());
+        debug!("{}", format!("Added Global Rule : {:?} - {:?}", r.name(), r.query().ok()).bright_blue());
         self.global_rules.push(r);
       }
     }
   }
 
+  /// Merges the global rules and global tags discovered by `other` (i.e. the copy of the rule store
+  /// used for processing a file) into `self`. Global rules already present in `self` are skipped.
+  pub(crate) fn merge_global_rules_and_tags(&mut self, other: &RuleStore) {
+    for rule in &other.global_rules {
+      if !self.global_rules.iter().any(|r| {
+        r.name().eq(&rule.name())
+          && r.replace().ok().eq(&rule.replace().ok())
+          && r.query().ok().eq(&rule.query().ok())
+      }) {
+        self.global_rules.push(rule.clone());
+      }
+    }
+    self.global_tags.extend(other.global_tags.clone());
+  }
+
   /// Get the compiled query for the `query_str` from the cache
   /// else compile it, add it to the cache and return it.
-  pub(crate) fn query(&mut self, query_str: &String) -> &Query {
-    let language = self.language();
-    self
-      .rule_query_cache
-      .entry(query_str.to_string())
-      .or_insert_with(|| query_str.create_query(language))
+  /// The query is compiled without holding the lock, so that the other threads are not blocked meanwhile.
+  pub(crate) fn query(&self, query_str: &String) -> Result<Arc<Query>, PiranhaError> {
+    let rule_query_cache = &self.shared.rule_query_cache;
+    if let Some(query) = rule_query_cache.read().unwrap().get(query_str) {
+      return Ok(query.clone());
+    }
+    let query = Arc::new(query_str.create_query(self.language())?);
+    // Another thread may have compiled (and cached) the same query in the meantime
+    Ok(
+      rule_query_cache
+        .write()
+        .unwrap()
+        .entry(query_str.to_string())
+        .or_insert(query)
+        .clone(),
+    )
   }
 
   /// Get the next rules to be applied grouped by the scope in which they should be performed.
   pub(crate) fn get_next(
     &self, rule_name: &String, tag_matches: &HashMap<String, String>,
-  ) -> HashMap<String, Vec<Rule>> {
+  ) -> Result<HashMap<String, Vec<Rule>>, PiranhaError> {
     // let rule_name = rule.name();
     let mut next_rules: HashMap<String, Vec<Rule>> = HashMap::new();
     // Iterate over each entry (Edge) in the adjacency list corresponding to `rule_name`
-    for (scope, to_rule) in self.rule_graph.get_neighbors(rule_name) {
-      let to_rule_name = &self.rules_by_name[&to_rule];
+    for (scope, to_rule) in self.shared.rule_graph.get_neighbors(rule_name) {
+      let to_rule_name = &self.shared.rules_by_name[&to_rule];
       // If the to_rule_name is a dummy rule, skip it and rather return it's next rules.
       if to_rule_name.is_dummy_rule() {
         // Call this method recursively on the dummy node
         for (next_next_rules_scope, next_next_rules) in
-          self.get_next(&to_rule_name.name(), tag_matches)
+          self.get_next(&to_rule_name.name(), tag_matches)?
         {
           for next_next_rule in next_next_rules {
             // Group the next rules based on the scope
             next_rules.collect(
               String::from(&next_next_rules_scope),
-              next_next_rule.instantiate(tag_matches),
+              next_next_rule.instantiate(tag_matches)?,
             )
           }
         }
       } else {
         // Group the next rules based on the scope
-        next_rules.collect(String::from(&scope), to_rule_name.instantiate(tag_matches));
+        next_rules.collect(String::from(&scope), to_rule_name.instantiate(tag_matches)?);
       }
     }
     // Add empty entry, incase no next rule was found for a particular scope
     for scope in [PARENT, GLOBAL] {
       next_rules.entry(scope.to_string()).or_default();
     }
-    next_rules
+    Ok(next_rules)
   }
 
   // For the given scope level, get the ScopeQueryGenerator from the `scope_config.toml` file
   pub(crate) fn get_scope_query_generators(&self, scope_level: &str) -> Vec<ScopeQueryGenerator> {
     self
+      .shared
       .scopes
       .iter()
       .find(|level| level.name().eq(scope_level))
@@ -176,40 +277,42 @@ impl RuleStore {
   pub(crate) fn add_global_tags(&mut self, new_entries: &HashMap<String, String>) {
     let global_substitutions: HashMap<String, String> = new_entries
       .iter()
-      .filter(|e| e.0.starts_with(self.piranha_args.global_tag_prefix()))
+      .filter(|e| e.0.starts_with(self.piranha_args().global_tag_prefix()))
       .map(|(a, b)| (a.to_string(), b.to_string()))
       .collect();
     let _ = &self.global_tags.extend(global_substitutions);
   }
 
   pub(crate) fn piranha_args(&self) -> &PiranhaArguments {
-    &self.piranha_args
+    &self.shared.piranha_args
   }
 }
 
 #[cfg(test)]
 impl RuleStore {
   pub(crate) fn dummy() -> RuleStore {
-    RuleStore {
-      rule_graph: RuleGraph::dummy(),
-      rule_query_cache: HashMap::new(),
-      rules_by_name: HashMap::new(),
-      global_rules: vec![],
-      piranha_args: PiranhaArguments::default(),
-      scopes: vec![],
-      global_tags: HashMap::new(),
-    }
+    RuleStore::dummy_with_scope(vec![])
   }
 
   pub(crate) fn dummy_with_scope(scopes: Vec<ScopeGenerator>) -> RuleStore {
+    RuleStore::dummy_with_rule_graph(vec![], vec![], scopes)
+  }
+
+  pub(crate) fn dummy_with_rule_graph(
+    rules: Vec<Rule>, edges: Vec<OutgoingEdges>, scopes: Vec<ScopeGenerator>,
+  ) -> RuleStore {
+    let rule_graph = RuleGraph::new(&edges, &rules);
+    let rules_by_name = rules.iter().map(|r| (r.name(), r.clone())).collect();
     RuleStore {
-      rule_graph: RuleGraph::dummy(),
-      rule_query_cache: HashMap::new(),
-      rules_by_name: HashMap::new(),
+      shared: SharedRuleStore::new(
+        rule_graph,
+        rules_by_name,
+        scopes,
+        PiranhaArguments::default(),
+      ),
       global_rules: vec![],
-      piranha_args: PiranhaArguments::default(),
-      scopes,
       global_tags: HashMap::new(),
+      deadline: None,
     }
   }
 }
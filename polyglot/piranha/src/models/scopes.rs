@@ -19,7 +19,7 @@ use crate::utilities::tree_sitter_utilities::{
   get_node_for_range, substitute_tags, PiranhaHelpers,
 };
 
-use super::{rule_store::RuleStore, source_code_unit::SourceCodeUnit};
+use super::{piranha_error::PiranhaError, rule_store::RuleStore, source_code_unit::SourceCodeUnit};
 
 // Represents the content in the `scope_config.toml` file
 #[derive(Deserialize, Debug, Clone, Hash, PartialEq, Eq, Default)]
@@ -53,10 +53,11 @@ impl ScopeGenerator {
 
   /// Generate a tree-sitter based query representing the scope of the previous edit.
   /// We generate these scope queries by matching the rules provided in `<lang>_scopes.toml`.
+  /// Returns `None` if no enclosing node matches any of the scope matchers.
   pub(crate) fn get_scope_query(
-    source_code_unit: SourceCodeUnit, scope_level: &str, start_byte: usize, end_byte: usize,
+    source_code_unit: &SourceCodeUnit, scope_level: &str, start_byte: usize, end_byte: usize,
     rules_store: &mut RuleStore,
-  ) -> String {
+  ) -> Result<Option<String>, PiranhaError> {
     let root_node = source_code_unit.root_node();
     let mut changed_node = get_node_for_range(root_node, start_byte, end_byte);
     // Get the scope matchers for `scope_level` from the `scope_config.toml`.
@@ -70,14 +71,17 @@ impl ScopeGenerator {
         changed_node.kind()
       );
       for m in &scope_matchers {
-        if let Some(p_match) = changed_node.get_match_for_query(
-          &source_code_unit.code(),
-          rules_store.query(&m.matcher()),
-          false,
-        ) {
+        let matcher_query = rules_store.query(&m.matcher())?;
+        if let Some(p_match) =
+          changed_node.get_match_for_query(source_code_unit.code(), &matcher_query, false)
+        {
           // Generate the scope query for the specific context by substituting the
           // the tags with code snippets appropriately in the `generator` query.
-          return substitute_tags(m.generator(), p_match.matches(), true);
+          return Ok(Some(substitute_tags(
+            m.generator(),
+            p_match.matches(),
+            true,
+          )));
         }
       }
       if let Some(parent) = changed_node.parent() {
@@ -86,7 +90,7 @@ impl ScopeGenerator {
         break;
       }
     }
-    panic!("Could not create scope query for {:?}", scope_level);
+    Ok(None)
   }
 }
 
@@ -108,7 +112,7 @@ impl ScopeQueryGenerator {
 
 #[cfg(test)]
 impl ScopeQueryGenerator {
-  fn new(matcher: &str, generator: &str) -> ScopeQueryGenerator {
+  pub(crate) fn new(matcher: &str, generator: &str) -> ScopeQueryGenerator {
     ScopeQueryGenerator {
       matcher: matcher.to_string(),
       generator: generator.to_string(),
@@ -117,7 +121,7 @@ impl ScopeQueryGenerator {
 }
 #[cfg(test)]
 impl ScopeGenerator {
-  fn new(name: &str, rules: Vec<ScopeQueryGenerator>) -> ScopeGenerator {
+  pub(crate) fn new(name: &str, rules: Vec<ScopeQueryGenerator>) -> ScopeGenerator {
     ScopeGenerator {
       name: name.to_string(),
       rules,
@@ -16,7 +16,7 @@ use std::{
   path::{Path, PathBuf},
 };
 
-use log::{debug, error};
+use log::debug;
 use regex::Regex;
 use tree_sitter::{InputEdit, Node, Parser, Range, Tree};
 use tree_sitter_traversal::{traverse, Order};
@@ -24,9 +24,20 @@ use tree_sitter_traversal::{traverse, Order};
 use crate::utilities::tree_sitter_utilities::{get_tree_sitter_edit, TreeSitterHelpers};
 
 use super::{
-  edit::Edit, matches::Match, piranha_arguments::PiranhaArguments, rule_store::RuleStore,
+  diagnostic::Diagnostic, edit::Edit, matches::Match, piranha_arguments::PiranhaArguments,
+  piranha_error::PiranhaError, rule_store::RuleStore,
 };
 
+/// The state of a source code unit before the application of a rule, to which it can be rolled back.
+/// Only the code and the AST are kept, since the rewrites, matches and diagnostics can only grow (they are truncated back).
+pub(crate) struct Checkpoint {
+  ast: Tree,
+  code: String,
+  number_of_rewrites: usize,
+  number_of_matches: usize,
+  number_of_diagnostics: usize,
+}
+
 // Maintains the updated source code content and AST of the file
 #[derive(Clone)]
 pub(crate) struct SourceCodeUnit {
@@ -43,31 +54,56 @@ pub(crate) struct SourceCodeUnit {
   rewrites: Vec<Edit>,
   // Matches for the read_only rules in this source code unit
   matches: Vec<(String, Match)>,
+  // Rule applications that were rolled back for this source code unit
+  diagnostics: Vec<Diagnostic>,
   // Piranha Arguments passed by the user
   piranha_arguments: PiranhaArguments,
 }
 
 impl SourceCodeUnit {
+  /// Parses the `code` into a new source code unit.
+  /// Returns `PiranhaError::TimeoutExceeded` if the parsing is cancelled because of the timeout of the `parser`.
   pub(crate) fn new(
     parser: &mut Parser, code: String, substitutions: &HashMap<String, String>, path: &Path,
     piranha_arguments: &PiranhaArguments,
-  ) -> Self {
-    let ast = parser.parse(&code, None).expect("Could not parse code");
-    Self {
+  ) -> Result<Self, PiranhaError> {
+    let ast = parse(parser, &code, None)?;
+    Ok(Self {
       ast,
       code,
       substitutions: substitutions.clone(),
       path: path.to_path_buf(),
       rewrites: Vec::new(),
       matches: Vec::new(),
+      diagnostics: Vec::new(),
       piranha_arguments: piranha_arguments.clone(),
-    }
+    })
   }
 
   pub(crate) fn root_node(&self) -> Node<'_> {
     self.ast.root_node()
   }
 
+  /// Captures the current state of the source code unit (see `rollback`).
+  pub(crate) fn checkpoint(&self) -> Checkpoint {
+    Checkpoint {
+      ast: self.ast.clone(),
+      code: self.code.clone(),
+      number_of_rewrites: self.rewrites.len(),
+      number_of_matches: self.matches.len(),
+      number_of_diagnostics: self.diagnostics.len(),
+    }
+  }
+
+  /// Restores the state captured by `checkpoint`, discarding the rewrites, matches and diagnostics recorded since.
+  pub(crate) fn rollback(&mut self, checkpoint: Checkpoint) {
+    self.ast = checkpoint.ast;
+    self.code = checkpoint.code;
+    self.rewrites.truncate(checkpoint.number_of_rewrites);
+    self.matches.truncate(checkpoint.number_of_matches);
+    self.diagnostics.truncate(checkpoint.number_of_diagnostics);
+  }
+
   /// Writes the current contents of `code` to the file system.
   /// Based on the user's specifications, this function will delete a file if empty
   /// and replace three consecutive newline characters with two.
@@ -79,18 +115,38 @@ impl SourceCodeUnit {
     } else {
       let content = if *piranha_arguments.delete_consecutive_new_lines() {
         let regex = Regex::new(r"\n(\s*\n)+(\s*\n)").unwrap();
-        regex.replace_all(&self.code(), "\n${2}").to_string()
+        regex.replace_all(self.code(), "\n${2}").to_string()
       } else {
-        self.code()
+        self.code().to_string()
       };
       fs::write(&self.path, content).expect("Unable to Write file");
     }
   }
 
-  pub(crate) fn apply_edit(&mut self, edit: &Edit, parser: &mut Parser) -> InputEdit {
+  /// Applies the edit to the source code unit (and deletes the associated comment if applicable).
+  /// Returns `PiranhaError::SyntacticallyIncorrectRewrite` if the edit produces syntactically incorrect source code,
+  /// or `PiranhaError::TimeoutExceeded` if re-parsing the source code is cancelled because of the timeout of the `parser`.
+  pub(crate) fn apply_edit(
+    &mut self, edit: &Edit, parser: &mut Parser,
+  ) -> Result<InputEdit, PiranhaError> {
+    let check_syntax = |source_code_unit: &Self, applied_edit: &InputEdit| {
+      if !source_code_unit.ast.root_node().has_error() {
+        return Ok(());
+      }
+      let message = format!(
+        "Rule {} produced syntactically incorrect source code at bytes {}..{}",
+        edit.matched_rule(),
+        applied_edit.start_byte,
+        applied_edit.new_end_byte
+      );
+      Err(PiranhaError::SyntacticallyIncorrectRewrite(Box::new(
+        Diagnostic::new(edit.matched_rule(), edit.clone(), message),
+      )))
+    };
     // Get the tree_sitter's input edit representation
     let mut applied_edit =
-      self._apply_edit(edit.replacement_range(), edit.replacement_string(), parser);
+      self._apply_edit(edit.replacement_range(), edit.replacement_string(), parser)?;
+    check_syntax(self, &applied_edit)?;
     // Check if the edit kind is "DELETE something"
     if self.piranha_arguments.cleanup_comments().clone() && edit.replacement_string().is_empty() {
       let deleted_at = edit.replacement_range().start_point.row;
@@ -100,10 +156,11 @@ impl SourceCodeUnit {
         edit.replacement_range().start_byte,
       ) {
         debug!("Deleting an associated comment");
-        applied_edit = self._apply_edit(comment_range, "", parser);
+        applied_edit = self._apply_edit(comment_range, "", parser)?;
+        check_syntax(self, &applied_edit)?;
       }
     }
-    return applied_edit;
+    Ok(applied_edit)
   }
 
   /// This function reports the range of the comment associated to the deleted element.
@@ -163,13 +220,15 @@ impl SourceCodeUnit {
   /// * `parser`
   ///
   /// # Returns
-  /// The `edit:InputEdit` performed.
+  /// The `edit:InputEdit` performed, or an error if re-parsing the updated source code is cancelled (timeout).
+  /// Note that the updated source code can be syntactically incorrect.
   ///
-  /// Note - Causes side effect. - Updates `self.ast` and `self.code`
+  /// Note - Causes side effect. - Updates `self.ast` and `self.code`.
+  /// It is the caller's responsibility to restore the last good state of the source code unit.
   pub(crate) fn _apply_edit(
     &mut self, range: Range, replacement_string: &str, parser: &mut Parser,
-  ) -> InputEdit {
-    // Check if the edit is a `Delete` operation then delete trailing comma
+  ) -> Result<InputEdit, PiranhaError> {
+    // Check if the edit is a `Delete` operation then delete trailing (or preceding) comma
     let replace_range = if replacement_string.trim().is_empty() {
       self.delete_trailing_comma(range)
     } else {
@@ -177,19 +236,11 @@ impl SourceCodeUnit {
     };
     // Get the tree_sitter's input edit representation
     let (new_source_code, ts_edit) =
-      get_tree_sitter_edit(self.code.clone(), replace_range, replacement_string);
+      get_tree_sitter_edit(&self.code, replace_range, replacement_string);
     // Apply edit to the tree
     self.ast.edit(&ts_edit);
-    self._replace_file_contents_and_re_parse(&new_source_code, parser, true);
-    if self.ast.root_node().has_error() {
-      let msg = format!(
-        "Produced syntactically incorrect source code {}",
-        self.code()
-      );
-      error!("{}", msg);
-      panic!("{}", msg);
-    }
-    ts_edit
+    self._replace_file_contents_and_re_parse(&new_source_code, parser, true)?;
+    Ok(ts_edit)
   }
 
   /// Deletes the trailing comma after the {deleted_range}
@@ -203,6 +254,7 @@ impl SourceCodeUnit {
   /// Get the node after the {deleted_range}'s end byte (heuristic 5 characters) 
   /// Traverse this node and get the node closest to the range {deleted_range}'s end byte
   /// IF this closest node is a comma, extend the {new_delete_range} to include the comma.
+  /// ELSE IF the language always deletes the preceding comma (e.g. json), delete it (if any).
   fn delete_trailing_comma(&mut self, deleted_range: Range) -> Range {
     let mut new_deleted_range = deleted_range;
 
@@ -230,6 +282,63 @@ impl SourceCodeUnit {
         {
           new_deleted_range.end_byte = node_after_to_be_deleted_node.end_byte();
           new_deleted_range.end_point = node_after_to_be_deleted_node.end_position();
+          return new_deleted_range;
+        }
+      }
+    }
+    if self
+      .piranha_arguments
+      .language_name()
+      .deletes_preceding_comma()
+    {
+      return self.delete_preceding_comma(new_deleted_range);
+    }
+    new_deleted_range
+  }
+
+  /// Deletes the comma before the {deleted_range}.
+  /// This handles the deletion of the last element of a comma separated list (e.g. `[a, b]` ->
+  /// `[a]`), which would otherwise leave a trailing comma behind (syntactically incorrect in JSON).
+  /// # Arguments
+  /// * `deleted_range` - the range of the deleted code (without a trailing comma)
+  ///
+  /// # Returns
+  /// code range of the closest node
+  ///
+  /// Algorithm:
+  /// Get the node before the {deleted_range}'s start byte
+  /// Traverse this node and get the node closest to the range {deleted_range}'s start byte
+  /// IF this closest node is a comma, extend the {new_delete_range} to include the comma.
+  fn delete_preceding_comma(&mut self, deleted_range: Range) -> Range {
+    let mut new_deleted_range = deleted_range;
+    if deleted_range.start_byte == 0 {
+      return new_deleted_range;
+    }
+
+    // Get the node immediately before the to-be-deleted code
+    if let Some(parent_node) = self
+      .ast
+      .root_node()
+      .descendant_for_byte_range(deleted_range.start_byte - 1, deleted_range.start_byte)
+      .and_then(|n| n.parent())
+    {
+      // Traverse this `parent_node` to find the closest previous node before the `replace_range`
+      if let Some(node_before_to_be_deleted_node) = traverse(parent_node.walk(), Order::Post)
+        .filter(|n| n.end_byte() <= deleted_range.start_byte)
+        .min_by(|a, b| {
+          (deleted_range.start_byte - a.end_byte()).cmp(&(deleted_range.start_byte - b.end_byte()))
+        })
+      {
+        // If the previous closest node to the "to be deleted node" is a comma, extend
+        // the deletion range to include the comma
+        if node_before_to_be_deleted_node
+          .utf8_text(self.code().as_bytes())
+          .unwrap()
+          .trim()
+          .eq(",")
+        {
+          new_deleted_range.start_byte = node_before_to_be_deleted_node.start_byte();
+          new_deleted_range.start_point = node_before_to_be_deleted_node.start_position();
         }
       }
     }
@@ -244,23 +353,21 @@ impl SourceCodeUnit {
   /// Note - Causes side effect. - Updates `self.ast` and `self.code`
   pub(crate) fn _replace_file_contents_and_re_parse(
     &mut self, replacement_content: &str, parser: &mut Parser, is_current_ast_edited: bool,
-  ) {
+  ) -> Result<(), PiranhaError> {
     let prev_tree = if is_current_ast_edited {
       Some(&self.ast)
     } else {
       None
     };
     // Create a new updated tree from the previous tree
-    let new_tree = parser
-      .parse(&replacement_content, prev_tree)
-      .expect("Could not generate new tree!");
+    let new_tree = parse(parser, replacement_content, prev_tree)?;
     self.ast = new_tree;
     self.code = replacement_content.to_string();
+    Ok(())
   }
 
-  // #[cfg(test)] // Rust analyzer FP
-  pub(crate) fn code(&self) -> String {
-    String::from(&self.code)
+  pub(crate) fn code(&self) -> &str {
+    &self.code
   }
 
   pub(crate) fn substitutions(&self) -> &HashMap<String, String> {
@@ -293,6 +400,26 @@ impl SourceCodeUnit {
   pub(crate) fn matches_mut(&mut self) -> &mut Vec<(String, Match)> {
     &mut self.matches
   }
+
+  pub(crate) fn diagnostics(&self) -> &[Diagnostic] {
+    self.diagnostics.as_ref()
+  }
+
+  pub(crate) fn diagnostics_mut(&mut self) -> &mut Vec<Diagnostic> {
+    &mut self.diagnostics
+  }
+}
+
+/// Parses the `code` (reusing the `old_tree`, if provided).
+/// The parsing is cancelled once the timeout of the `parser` (i.e. the remaining time budget, see `RuleStore::set_parser_timeout`)
+/// is exceeded.
+fn parse(parser: &mut Parser, code: &str, old_tree: Option<&Tree>) -> Result<Tree, PiranhaError> {
+  parser.parse(code, old_tree).ok_or_else(|| {
+    PiranhaError::TimeoutExceeded(format!(
+      "parsing was cancelled after {} microseconds",
+      parser.timeout_micros()
+    ))
+  })
 }
 
 #[cfg(test)]
@@ -0,0 +1,169 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use {
+  super::{CombinedMatches, CombinedQuery},
+  crate::{
+    models::{rule::Rule, rule_store::RuleStore, source_code_unit::SourceCodeUnit},
+    utilities::tree_sitter_utilities::get_parser,
+  },
+  std::collections::HashSet,
+};
+
+fn get_rules() -> Vec<Rule> {
+  vec![
+    Rule::new(
+      "replace_is_enabled",
+      "((method_invocation name: (_) @name) @mi (#eq? @name \"isEnabled\"))",
+      "mi",
+      "true",
+      HashSet::new(),
+      HashSet::new(),
+    ),
+    // A rule whose query consists of two patterns (both matching the same node)
+    Rule::new(
+      "delete_true_declaration",
+      "((local_variable_declaration) @d)\n((local_variable_declaration declarator: (variable_declarator value: (true))) @d)",
+      "d",
+      "",
+      HashSet::new(),
+      HashSet::new(),
+    ),
+    Rule::new(
+      "replace_bar",
+      "((method_invocation name: (_) @name) @mi (#eq? @name \"bar\"))",
+      "mi",
+      "baz()",
+      HashSet::new(),
+      HashSet::new(),
+    ),
+  ]
+}
+
+// Gets the code snippet of each match of each rule.
+fn get_matched_code(
+  matches: &CombinedMatches, number_of_rules: usize, source_code: &str,
+) -> Vec<Vec<String>> {
+  (0..number_of_rules)
+    .map(|index| {
+      matches
+        .of(index)
+        .map(|m| source_code[m.range().start_byte..m.range().end_byte].to_string())
+        .collect()
+    })
+    .collect()
+}
+
+/// Tests that the matches of the combined query are routed to the rules (by pattern index).
+#[test]
+fn test_get_matches() {
+  let source_code = "class Test {
+      public void foobar(){
+        boolean x = isEnabled(STALE_FLAG);
+        bar();
+      }
+    }";
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut rule_store = RuleStore::dummy();
+  let source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let combined_query = CombinedQuery::new(&get_rules(), &mut rule_store).unwrap();
+  let matches = combined_query.get_matches(&source_code_unit, 0..source_code.len());
+
+  assert_eq!(
+    get_matched_code(&matches, 3, source_code),
+    vec![
+      vec!["isEnabled(STALE_FLAG)".to_string()],
+      vec![],
+      vec!["bar()".to_string()]
+    ]
+  );
+  // A match only maps the tags of its own rule's query
+  let bar_match = matches.of(2).next().unwrap();
+  assert_eq!(
+    bar_match.matches().keys().cloned().collect::<HashSet<_>>(),
+    HashSet::from(["name".to_string(), "mi".to_string()])
+  );
+}
+
+/// Tests that after a change, the matches before and after the changed range are kept (the latter being shifted),
+/// and the changed range is re-queried.
+#[test]
+fn test_update_matches() {
+  let source_code = "class Test {
+      public void foobar(){
+        boolean x = isEnabled(STALE_FLAG);
+        bar();
+      }
+    }";
+  let updated_source_code = "class Test {
+      public void foobar(){
+        boolean x = true;
+        bar();
+      }
+    }";
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut rule_store = RuleStore::dummy();
+  let source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name.clone());
+  let updated_source_code_unit =
+    SourceCodeUnit::default(updated_source_code, &mut parser, language_name);
+
+  let combined_query = CombinedQuery::new(&get_rules(), &mut rule_store).unwrap();
+  let mut matches = combined_query.get_matches(&source_code_unit, 0..source_code.len());
+  matches.update(
+    &updated_source_code_unit,
+    source_code,
+    0..updated_source_code.len(),
+  );
+
+  assert_eq!(
+    get_matched_code(&matches, 3, updated_source_code),
+    vec![
+      vec![],
+      vec!["boolean x = true;".to_string()],
+      vec!["bar()".to_string()]
+    ]
+  );
+  // The shifted match has the same range as the match of the updated source code
+  let bar = updated_source_code.find("bar();").unwrap();
+  assert_eq!(
+    matches.of(2).next().unwrap().range(),
+    combined_query
+      .get_matches(&updated_source_code_unit, 0..updated_source_code.len())
+      .of(2)
+      .next()
+      .unwrap()
+      .range()
+  );
+  assert_eq!(matches.of(2).next().unwrap().range().start_byte, bar);
+}
+
+/// Tests that an empty group of rules has no matches.
+#[test]
+fn test_get_matches_empty() {
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut rule_store = RuleStore::dummy();
+  let source_code_unit = SourceCodeUnit::default("class Test {}", &mut parser, language_name);
+
+  let combined_query = CombinedQuery::new(&[], &mut rule_store).unwrap();
+
+  assert!(get_matched_code(
+    &combined_query.get_matches(&source_code_unit, 0..13),
+    0,
+    "class Test {}"
+  )
+  .is_empty());
+}
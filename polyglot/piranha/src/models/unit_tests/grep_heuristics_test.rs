@@ -0,0 +1,156 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use {
+  super::GrepHeuristics,
+  crate::{
+    models::{
+      piranha_error::PiranhaError,
+      rule::{Rule, Rules},
+    },
+    utilities::parse_toml,
+  },
+  std::collections::HashMap,
+};
+
+/// Parses the `rules_toml`, and instantiates the (global) rules with the given `substitutions`.
+fn get_global_rules(rules_toml: &str, substitutions: &HashMap<String, String>) -> Vec<Rule> {
+  let rules: Rules = parse_toml(rules_toml).unwrap();
+  rules
+    .rules
+    .iter()
+    .map(|r| {
+      let mut rule = r.instantiate(substitutions).unwrap();
+      rule.add_grep_heuristics_for_global_rules(substitutions);
+      rule
+    })
+    .collect()
+}
+
+/// Tests that the values of the holes are used as (escaped) grep heuristics, except the excluded holes.
+#[test]
+fn test_grep_heuristics_default() {
+  let rules = get_global_rules(
+    r#"
+    [[rules]]
+    name = "replace_flag"
+    query = "((identifier) @id (#eq? @id \"@stale_flag_name\"))"
+    replace_node = "id"
+    replace = "@treated"
+    holes = ["stale_flag_name", "treated"]
+    grep_heuristics_excluded_holes = ["treated"]
+    "#,
+    &HashMap::from([
+      ("stale_flag_name".to_string(), "STALE.FLAG(".to_string()),
+      ("treated".to_string(), "true".to_string()),
+    ]),
+  );
+  let grep_heuristics = GrepHeuristics::new(&rules).unwrap();
+  assert!(grep_heuristics.is_match("exp.isEnabled(STALE.FLAG());"));
+  assert!(!grep_heuristics.is_match("exp.isEnabled(STALE_FLAG_1); boolean x = true;"));
+}
+
+/// Tests that the values of the excluded holes and the empty values are not used as grep heuristics.
+#[test]
+fn test_grep_heuristics_excluded_and_empty_values() {
+  let rules = get_global_rules(
+    r#"
+    [[rules]]
+    name = "replace_flag"
+    query = "((identifier) @id (#eq? @id \"@namespace@stale_flag_name\"))"
+    replace_node = "id"
+    replace = "true"
+    holes = ["stale_flag_name", "namespace", "prefix"]
+    grep_heuristics_excluded_holes = ["namespace"]
+
+    [[rules]]
+    name = "replace_flag_key"
+    query = "((string_literal) @s (#eq? @s \"\\\"@prefix@stale_flag_name\\\"\"))"
+    replace_node = "s"
+    replace = "true"
+    holes = ["stale_flag_name", "prefix"]
+    grep_heuristics = ["@prefix", "@prefix@stale_flag_name"]
+    "#,
+    &HashMap::from([
+      ("stale_flag_name".to_string(), "STALE_FLAG".to_string()),
+      ("namespace".to_string(), "Experiments.".to_string()),
+      ("prefix".to_string(), "".to_string()),
+    ]),
+  );
+  let grep_heuristics = GrepHeuristics::new(&rules).unwrap();
+  assert!(grep_heuristics.is_match("exp.isEnabled(Experiments.STALE_FLAG);"));
+  assert!(!grep_heuristics.is_match("exp.isEnabled(Experiments.OTHER_FLAG);"));
+}
+
+/// Tests that the declared literal and regex grep heuristics are used (with the holes substituted).
+#[test]
+fn test_grep_heuristics_declared() {
+  let rules = get_global_rules(
+    r#"
+    [[rules]]
+    name = "replace_flag"
+    query = "((identifier) @id (#eq? @id \"@stale_flag_name\"))"
+    replace_node = "id"
+    replace = "@treated"
+    holes = ["stale_flag_name", "treated"]
+    grep_heuristics = ["KEY_@stale_flag_name"]
+    grep_heuristics_regex = ['isToggleEnabled\(\w+\.@stale_flag_name\)']
+    "#,
+    &HashMap::from([
+      ("stale_flag_name".to_string(), "STALE.FLAG".to_string()),
+      ("treated".to_string(), "true".to_string()),
+    ]),
+  );
+  let grep_heuristics = GrepHeuristics::new(&rules).unwrap();
+  assert!(grep_heuristics.is_match("String key = KEY_STALE.FLAG;"));
+  assert!(grep_heuristics.is_match("exp.isToggleEnabled(Experiment.STALE.FLAG)"));
+  assert!(!grep_heuristics.is_match("exp.isToggleEnabled(Experiment.STALExFLAG)"));
+  assert!(!grep_heuristics.is_match("exp.isEnabled(STALE.FLAG)"));
+}
+
+/// Tests that all the files are matched if some rule has no grep heuristics.
+#[test]
+fn test_grep_heuristics_match_all() {
+  let rules = get_global_rules(
+    r#"
+    [[rules]]
+    name = "delete_empty_method"
+    query = "((method_declaration) @md)"
+    replace_node = "md"
+    replace = ""
+    "#,
+    &HashMap::new(),
+  );
+  let grep_heuristics = GrepHeuristics::new(&rules).unwrap();
+  assert!(grep_heuristics.is_match("class A { }"));
+}
+
+/// Tests that an invalid regex grep heuristic is reported as an invalid rule.
+#[test]
+fn test_grep_heuristics_invalid_regex() {
+  let rules = get_global_rules(
+    r#"
+    [[rules]]
+    name = "invalid_grep_heuristic"
+    query = "((identifier) @id)"
+    replace_node = "id"
+    replace = ""
+    grep_heuristics_regex = ['isToggleEnabled(']
+    "#,
+    &HashMap::new(),
+  );
+  assert!(matches!(
+    GrepHeuristics::new(&rules),
+    Err(PiranhaError::InvalidRule { .. })
+  ));
+}
@@ -0,0 +1,100 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use {
+  super::RuleGraph,
+  crate::{
+    models::{outgoing_edges::Edges, rule::Rule},
+    utilities::parse_toml,
+  },
+  std::collections::HashSet,
+};
+
+/// Creates a rule graph with the rules `a`, `b`, `c`, `d` and `e` and the edges in `edges_toml`.
+fn get_rule_graph(edges_toml: &str) -> RuleGraph {
+  let edges: Edges = parse_toml(edges_toml).unwrap();
+  let rules = ["a", "b", "c", "d", "e"]
+    .iter()
+    .map(|name| {
+      Rule::new(
+        name,
+        "(identifier) @x",
+        "x",
+        "",
+        HashSet::new(),
+        HashSet::new(),
+      )
+    })
+    .collect();
+  RuleGraph::new(&edges.edges, &rules)
+}
+
+#[test]
+fn test_get_cycle() {
+  let rule_graph = get_rule_graph(
+    r#"
+    [[edges]]
+    scope = "Parent"
+    from = "a"
+    to = ["b"]
+
+    [[edges]]
+    scope = "Method"
+    from = "b"
+    to = ["c", "d"]
+
+    [[edges]]
+    scope = "Global"
+    from = "d"
+    to = ["a"]
+
+    [[edges]]
+    scope = "Parent"
+    from = "e"
+    to = ["e"]
+    "#,
+  );
+
+  let cycle = vec!["a", "b", "d", "a"];
+  assert_eq!(rule_graph.get_cycle(&"a".to_string()).unwrap(), cycle);
+  assert_eq!(
+    rule_graph.get_cycle(&"d".to_string()).unwrap(),
+    vec!["d", "a", "b", "d"]
+  );
+  assert_eq!(
+    rule_graph.get_cycle(&"e".to_string()).unwrap(),
+    vec!["e", "e"]
+  );
+  assert!(rule_graph.get_cycle(&"c".to_string()).is_none());
+
+  // The cycle through `a`, `b` and `d` is reported once.
+  assert_eq!(rule_graph.get_cycles(), vec![cycle, vec!["e", "e"]]);
+}
+
+#[test]
+fn test_get_cycles_acyclic() {
+  let rule_graph = get_rule_graph(
+    r#"
+    [[edges]]
+    scope = "Parent"
+    from = "a"
+    to = ["b", "c"]
+
+    [[edges]]
+    scope = "Parent"
+    from = "b"
+    to = ["c"]
+    "#,
+  );
+  assert!(rule_graph.get_cycles().is_empty());
+}
@@ -24,7 +24,7 @@ use {
 
 /// Tests whether a valid rule can be correctly instantiated given valid substitutions.
 #[test]
-fn test_rule_try_instantiate_positive() {
+fn test_rule_instantiate_positive() {
   let holes = HashSet::from([String::from("variable_name")]);
   let rule = Rule::new("test","(((assignment_expression left: (_) @a.lhs right: (_) @a.rhs) @abc) (#eq? @a.lhs \"@variable_name\"))",
         "@abc", "",holes, HashSet::new());
@@ -32,23 +32,23 @@ fn test_rule_try_instantiate_positive() {
     (String::from("variable_name"), String::from("foobar")),
     (String::from("@a.lhs"), String::from("something")), // Should not substitute, since it `a.lhs` is not in `rule.holes`
   ]);
-  let instantiated_rule = rule.try_instantiate(&substitutions);
+  let instantiated_rule = rule.instantiate(&substitutions);
   assert!(instantiated_rule.is_ok());
   assert_eq!(
-    instantiated_rule.ok().unwrap().query(),
+    instantiated_rule.ok().unwrap().query().unwrap(),
     "(((assignment_expression left: (_) @a.lhs right: (_) @a.rhs) @abc) (#eq? @a.lhs \"foobar\"))"
   )
 }
 
 /// Tests whether a valid rule can be is *not* instantiated given invalid substitutions.
 #[test]
-fn test_rule_try_instantiate_negative() {
+fn test_rule_instantiate_negative() {
   let rule = Rule::new("test","(((assignment_expression left: (_) @a.lhs right: (_) @a.rhs) @abc) (#eq? @a.lhs \"@variable_name\"))",
         "abc", "",HashSet::from([String::from("variable_name")]), HashSet::new());
   let substitutions: HashMap<String, String> = HashMap::from([
     (String::from("@a.lhs"), String::from("something")), // Should not substitute, since it `a.lhs` is not in `rule.holes`
   ]);
-  let instantiated_rule = rule.try_instantiate(&substitutions);
+  let instantiated_rule = rule.instantiate(&substitutions);
   assert!(instantiated_rule.is_err());
 }
 
@@ -83,7 +83,7 @@ fn test_get_edit_positive_recursive() {
 
   let mut rule_store = RuleStore::dummy();
 
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
 
   let source_code_unit = SourceCodeUnit::new(
     &mut parser,
@@ -91,12 +91,17 @@ fn test_get_edit_positive_recursive() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     rule_store.piranha_args(),
-  );
+  )
+  .unwrap();
   let node = source_code_unit.root_node();
-  let matches = rule.get_matches(&source_code_unit, &mut rule_store, node, true);
+  let matches = rule
+    .get_matches(&source_code_unit, &mut rule_store, node, true)
+    .unwrap();
   assert!(!matches.is_empty());
 
-  let edit = rule.get_edit(&source_code_unit, &mut rule_store, node, true);
+  let edit = rule
+    .get_edit(&source_code_unit, &mut rule_store, node, true)
+    .unwrap();
   assert!(edit.is_some());
 }
 
@@ -130,7 +135,7 @@ fn test_get_edit_negative_recursive() {
         }";
 
   let mut rule_store = RuleStore::dummy();
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
 
   let source_code_unit = SourceCodeUnit::new(
     &mut parser,
@@ -138,11 +143,16 @@ fn test_get_edit_negative_recursive() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     rule_store.piranha_args(),
-  );
+  )
+  .unwrap();
   let node = source_code_unit.root_node();
-  let matches = rule.get_matches(&source_code_unit, &mut rule_store, node, true);
+  let matches = rule
+    .get_matches(&source_code_unit, &mut rule_store, node, true)
+    .unwrap();
   assert!(matches.is_empty());
-  let edit = rule.get_edit(&source_code_unit, &mut rule_store, node, true);
+  let edit = rule
+    .get_edit(&source_code_unit, &mut rule_store, node, true)
+    .unwrap();
   assert!(edit.is_none());
 }
 
@@ -170,7 +180,7 @@ fn test_get_edit_for_context_positive() {
 
   let mut rule_store = RuleStore::dummy();
 
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
 
   let source_code_unit = SourceCodeUnit::new(
     &mut parser,
@@ -178,15 +188,17 @@ fn test_get_edit_for_context_positive() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     rule_store.piranha_args(),
-  );
+  )
+  .unwrap();
   let edit = Rule::get_edit_for_context(
     &source_code_unit,
     41_usize,
     44_usize,
     &mut rule_store,
     &vec![rule],
-  );
-  // let edit = rule.get_edit(&source_code_unit, &mut rule_store, node, true);
+  )
+  .unwrap();
+  // let edit = rule.get_edit(&source_code_unit, &mut rule_store, node, true).unwrap();
   assert!(edit.is_some());
 }
 
@@ -215,7 +227,7 @@ fn test_get_edit_for_context_negative() {
 
   let mut rule_store = RuleStore::dummy();
 
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
 
   let source_code_unit = SourceCodeUnit::new(
     &mut parser,
@@ -223,14 +235,16 @@ fn test_get_edit_for_context_negative() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     rule_store.piranha_args(),
-  );
+  )
+  .unwrap();
   let edit = Rule::get_edit_for_context(
     &source_code_unit,
     29_usize,
     33_usize,
     &mut rule_store,
     &vec![rule],
-  );
-  // let edit = rule.get_edit(&source_code_unit, &mut rule_store, node, true);
+  )
+  .unwrap();
+  // let edit = rule.get_edit(&source_code_unit, &mut rule_store, node, true).unwrap();
   assert!(edit.is_none());
 }
@@ -69,7 +69,7 @@ fn test_get_scope_query_positive() {
 
   let mut rule_store =
     RuleStore::dummy_with_scope(vec![scope_generator_method, scope_generator_class]);
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
 
   let source_code_unit = SourceCodeUnit::new(
     &mut parser,
@@ -77,15 +77,13 @@ fn test_get_scope_query_positive() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     rule_store.piranha_args(),
-  );
+  )
+  .unwrap();
 
-  let scope_query_method = ScopeGenerator::get_scope_query(
-    source_code_unit.clone(),
-    "Method",
-    133,
-    134,
-    &mut rule_store,
-  );
+  let scope_query_method =
+    ScopeGenerator::get_scope_query(&source_code_unit, "Method", 133, 134, &mut rule_store)
+      .unwrap()
+      .unwrap();
 
   assert!(eq_without_whitespace(
     scope_query_method.as_str(),
@@ -103,7 +101,9 @@ fn test_get_scope_query_positive() {
   ));
 
   let scope_query_class =
-    ScopeGenerator::get_scope_query(source_code_unit, "Class", 133, 134, &mut rule_store);
+    ScopeGenerator::get_scope_query(&source_code_unit, "Class", 133, 134, &mut rule_store)
+      .unwrap()
+      .unwrap();
   assert!(eq_without_whitespace(
     scope_query_class.as_str(),
     "(
@@ -115,7 +115,6 @@ fn test_get_scope_query_positive() {
 
 /// Negative test for the generated scope query, given scope generators, source code and position of pervious edit.
 #[test]
-#[should_panic]
 fn test_get_scope_query_negative() {
   let scope_generator_method = ScopeGenerator::new(
     "Method",
@@ -163,7 +162,7 @@ fn test_get_scope_query_negative() {
 
   let mut rule_store =
     RuleStore::dummy_with_scope(vec![scope_generator_method, scope_generator_class]);
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
 
   let source_code_unit = SourceCodeUnit::new(
     &mut parser,
@@ -171,7 +170,10 @@ fn test_get_scope_query_negative() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     rule_store.piranha_args(),
-  );
+  )
+  .unwrap();
 
-  let _ = ScopeGenerator::get_scope_query(source_code_unit, "Method", 133, 134, &mut rule_store);
+  let scope_query =
+    ScopeGenerator::get_scope_query(&source_code_unit, "Method", 133, 134, &mut rule_store);
+  assert!(scope_query.unwrap().is_none());
 }
@@ -24,10 +24,22 @@ use crate::models::piranha_arguments::{PiranhaArguments, PiranhaArgumentsBuilder
 use {
   super::SourceCodeUnit,
   crate::{
-    models::edit::Edit, utilities::eq_without_whitespace,
+    models::{
+      edit::Edit,
+      outgoing_edges::Edges,
+      piranha_error::PiranhaError,
+      rule::Rule,
+      rule_store::RuleStore,
+      scopes::{ScopeGenerator, ScopeQueryGenerator},
+    },
+    utilities::eq_without_whitespace,
+    utilities::parse_toml,
     utilities::tree_sitter_utilities::get_parser,
   },
-  std::{collections::HashMap, path::PathBuf},
+  std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+  },
   tree_sitter::Range,
 };
 
@@ -43,6 +55,7 @@ impl SourceCodeUnit {
         .build()
         .unwrap(),
     )
+    .unwrap()
   }
 }
 
@@ -78,7 +91,7 @@ fn test_apply_edit_positive() {
     }";
 
   let language_name = String::from("java");
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
 
   let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
 
@@ -88,7 +101,7 @@ fn test_apply_edit_positive() {
   );
   assert!(eq_without_whitespace(
     &source_code.replace("boolean isFlagTreated = true;", ""),
-    &source_code_unit.code()
+    source_code_unit.code()
   ));
 }
 
@@ -107,7 +120,7 @@ fn test_apply_edit_negative() {
     }";
 
   let language_name = String::from("java");
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
   let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
 
   let _ = source_code_unit.apply_edit(
@@ -116,6 +129,170 @@ fn test_apply_edit_negative() {
   );
 }
 
+/// Negative test of an edit that produces syntactically incorrect code.
+/// The edit should be reported as a diagnostic instead of panicking.
+#[test]
+fn test_apply_edit_syntactically_incorrect() {
+  let source_code = "class Test {
+      public void foobar(){
+        doSomething();
+      }
+    }";
+
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let result = source_code_unit.apply_edit(
+    &Edit::dummy_edit(range(50, 63, 2, 8, 2, 21), String::from("doSomething(")),
+    &mut parser,
+  );
+  assert!(matches!(
+    result,
+    Err(PiranhaError::SyntacticallyIncorrectRewrite(_))
+  ));
+}
+
+/// Tests that a rule producing syntactically incorrect code is rolled back (without affecting the source code unit),
+/// and reported as a diagnostic.
+#[test]
+fn test_apply_rules_rollback_syntactically_incorrect_rewrite() {
+  let source_code = "class Test {
+      public void foobar(){
+        doSomething();
+      }
+    }";
+  let rule = Rule::new(
+    "test",
+    "((method_invocation name: (_) @name) @mi)",
+    "mi",
+    "@name(",
+    HashSet::new(),
+    HashSet::new(),
+  );
+
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut rule_store = RuleStore::dummy();
+  let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let result = source_code_unit.apply_rules(&mut rule_store, &[rule], &mut parser, None);
+
+  assert!(result.is_ok());
+
+  assert_eq!(source_code_unit.code(), source_code);
+  assert!(source_code_unit.rewrites().is_empty());
+  assert_eq!(source_code_unit.diagnostics().len(), 1);
+  assert_eq!(source_code_unit.diagnostics()[0].rule_name(), "test");
+  assert_eq!(
+    source_code_unit.diagnostics()[0]
+      .edit()
+      .replacement_string(),
+    "doSomething("
+  );
+}
+
+/// Tests that the application of a rule whose replacement matches its own query (i.e. a rule that never terminates)
+/// is stopped once it exceeds `max_rewrites_per_rule`, rolled back and reported as a diagnostic.
+#[test]
+fn test_apply_rules_rollback_rewrite_limit_exceeded() {
+  let source_code = "class Test {
+      public void foobar(){
+        doSomething();
+      }
+    }";
+  let rule = Rule::new(
+    "test",
+    "((method_invocation name: (_) @name) @mi)",
+    "mi",
+    "@name()",
+    HashSet::new(),
+    HashSet::new(),
+  );
+
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut rule_store = RuleStore::dummy();
+  let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let result = source_code_unit.apply_rules(&mut rule_store, &[rule], &mut parser, None);
+
+  assert!(result.is_ok());
+
+  assert_eq!(source_code_unit.code(), source_code);
+  assert!(source_code_unit.rewrites().is_empty());
+  assert_eq!(source_code_unit.diagnostics().len(), 1);
+  assert_eq!(source_code_unit.diagnostics()[0].rule_name(), "test");
+  assert!(source_code_unit.diagnostics()[0]
+    .message()
+    .contains("max_rewrites_per_rule"));
+}
+
+/// Tests that a rule whose change is not enclosed by the scope of its next rule (e.g. a change to a field
+/// followed by a `Method` scoped rule) is rolled back and reported as a diagnostic.
+#[test]
+fn test_apply_rules_rollback_scope_not_found() {
+  let source_code = "class Test {
+      int x = 1;
+      public void foobar(){
+        doSomething();
+      }
+    }";
+  let rule = Rule::new(
+    "test",
+    "((field_declaration (variable_declarator value: (decimal_integer_literal) @value)))",
+    "value",
+    "2",
+    HashSet::new(),
+    HashSet::new(),
+  );
+  let next_rule = Rule::new(
+    "next",
+    "((method_invocation name: (_) @name) @mi)",
+    "mi",
+    "",
+    HashSet::new(),
+    HashSet::new(),
+  );
+  let edges: Edges = parse_toml(
+    r#"
+    [[edges]]
+    scope = "Method"
+    from = "test"
+    to = ["next"]
+    "#,
+  )
+  .unwrap();
+  let scope_generator_method = ScopeGenerator::new(
+    "Method",
+    vec![ScopeQueryGenerator::new(
+      "((method_declaration name: (_) @n) @md)",
+      "(((method_declaration name: (_) @z) @qd) (#eq? @z \"@n\"))",
+    )],
+  );
+
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+  let mut rule_store = RuleStore::dummy_with_rule_graph(
+    vec![rule.clone(), next_rule],
+    edges.edges,
+    vec![scope_generator_method],
+  );
+  let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let result = source_code_unit.apply_rules(&mut rule_store, &[rule], &mut parser, None);
+
+  assert!(result.is_ok());
+
+  assert_eq!(source_code_unit.code(), source_code);
+  assert!(source_code_unit.rewrites().is_empty());
+  assert_eq!(source_code_unit.diagnostics().len(), 1);
+  assert_eq!(source_code_unit.diagnostics()[0].rule_name(), "test");
+  assert!(source_code_unit.diagnostics()[0]
+    .message()
+    .contains("Method"));
+}
+
 /// Positive test of an edit being applied  given replacement range  and replacement string.
 /// This scenario checks the logic that removes the comma identified by tree-sitter.
 #[test]
@@ -128,7 +305,7 @@ fn test_apply_edit_comma_handling_via_grammar() {
     }";
 
   let language_name = String::from("java");
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
 
   let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
 
@@ -138,7 +315,52 @@ fn test_apply_edit_comma_handling_via_grammar() {
   );
   assert!(eq_without_whitespace(
     &source_code.replace("\"NullAway\",", ""),
-    &source_code_unit.code()
+    source_code_unit.code()
+  ));
+}
+
+/// Positive test of an edit being applied  given replacement range  and replacement string.
+/// This scenario checks the logic that removes the preceding comma of a deleted last element.
+#[test]
+fn test_apply_edit_preceding_comma_handling_via_grammar() {
+  let source_code = "{\"enabled_flags\": [\"other_flag\", \"stale_flag\"]}";
+
+  let language_name = String::from("json");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+
+  let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let _ = source_code_unit.apply_edit(
+    &Edit::dummy_edit(range(33, 45, 0, 33, 0, 45), String::new()),
+    &mut parser,
+  );
+  assert!(eq_without_whitespace(
+    &source_code.replace(", \"stale_flag\"", ""),
+    source_code_unit.code()
+  ));
+}
+
+/// Positive test of an edit being applied  given replacement range  and replacement string.
+/// This scenario checks that the preceding comma of a deleted last element is kept, for a language
+/// that allows trailing commas.
+#[test]
+fn test_apply_edit_preceding_comma_kept_via_grammar() {
+  let source_code = "class Test {
+  String[] names = {\"NullAway\", \"FooBar\"};
+}";
+
+  let language_name = String::from("java");
+  let mut parser = get_parser(language_name.to_string()).unwrap();
+
+  let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
+
+  let _ = source_code_unit.apply_edit(
+    &Edit::dummy_edit(range(45, 53, 1, 32, 1, 40), String::new()),
+    &mut parser,
+  );
+  assert!(eq_without_whitespace(
+    &source_code.replace("\"FooBar\"", ""),
+    source_code_unit.code()
   ));
 }
 
@@ -156,7 +378,7 @@ fn test_apply_edit_comma_handling_via_regex() {
 
   let language_name = String::from("swift");
 
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
 
   let mut source_code_unit = SourceCodeUnit::default(source_code, &mut parser, language_name);
 
@@ -166,7 +388,7 @@ fn test_apply_edit_comma_handling_via_regex() {
   );
   assert!(eq_without_whitespace(
     &source_code.replace("name: \"BMX Bike\",", ""),
-    &source_code_unit.code()
+    source_code_unit.code()
   ));
 }
 fn execute_persist_in_temp_folder(
@@ -174,7 +396,7 @@ fn execute_persist_in_temp_folder(
   check_predicate: &dyn Fn(&TempDir) -> Result<bool, io::Error>,
 ) -> Result<bool, io::Error> {
   let language_name = String::from("java");
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
   let tmp_dir = TempDir::new("example")?;
   let file_path = &tmp_dir.path().join("Sample1.java");
   _ = fs::write(&file_path.as_path(), source_code);
@@ -188,7 +410,8 @@ fn execute_persist_in_temp_folder(
     &HashMap::new(),
     file_path.as_path(),
     &piranha_args,
-  );
+  )
+  .unwrap();
   source_code_unit.persist(args);
   check_predicate(&tmp_dir)
 }
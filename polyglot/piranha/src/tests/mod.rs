@@ -30,6 +30,38 @@ mod test_piranha_swift;
 
 mod test_piranha_python;
 
+mod test_piranha_go;
+
+mod test_piranha_ts;
+
+mod test_piranha_js;
+
+mod test_piranha_cs;
+
+mod test_piranha_objc;
+
+mod test_piranha_rb;
+
+mod test_piranha_scala;
+
+mod test_piranha_c;
+
+mod test_piranha_cpp;
+
+mod test_piranha_rs;
+
+mod test_piranha_php;
+
+mod test_piranha_dart;
+
+mod test_piranha_yaml;
+
+mod test_piranha_json;
+
+mod test_piranha_toml;
+
+mod test_piranha_xml;
+
 use std::sync::Once;
 
 static INIT: Once = Once::new();
@@ -49,8 +81,11 @@ fn run_match_test(relative_path_to_tests: &str, number_of_matches: usize) {
     path_to_codebase: format!("{path_to_test_ff}/input/"),
     path_to_configurations: format!("{path_to_test_ff}/configurations/"),
     path_to_output_summary: None,
-  });
-  let output_summaries = execute_piranha(&args, false);
+    include: vec![],
+    exclude: vec![],
+  })
+  .unwrap();
+  let output_summaries = execute_piranha(&args, false).unwrap();
 
   assert_eq!(
     output_summaries
@@ -71,8 +106,11 @@ fn run_rewrite_test(relative_path_to_tests: &str, n_files_changed: usize) {
     path_to_codebase: format!("{path_to_test_ff}/input/"),
     path_to_configurations: format!("{path_to_test_ff}/configurations/"),
     path_to_output_summary: None,
-  });
-  let output_summaries = execute_piranha(&args, false);
+    include: vec![],
+    exclude: vec![],
+  })
+  .unwrap();
+  let output_summaries = execute_piranha(&args, false).unwrap();
   // Checks if there are any rewrites performed for the file
   assert!(
     output_summaries
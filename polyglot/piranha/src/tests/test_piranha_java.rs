@@ -12,6 +12,11 @@ Copyright (c) 2022 Uber Technologies, Inc.
 */
 
 use super::{initialize, run_match_test, run_rewrite_test};
+use crate::{
+  config::CommandLineArguments,
+  execute_piranha,
+  models::{piranha_arguments::PiranhaArguments, piranha_error::PiranhaError},
+};
 
 static LANGUAGE: &str = "java";
 
@@ -57,6 +62,20 @@ fn test_java_scenarios_find_and_propagate() {
   run_rewrite_test(&format!("{}/{}", LANGUAGE, "find_and_propagate"), 2);
 }
 
+// Each file depends on the global tag found in the other file.
+// The result is the same whether the files are processed in parallel or sequentially (i.e. by a single thread).
+#[test]
+fn test_java_scenarios_global_tags_across_files() {
+  initialize();
+  let relative_path_to_tests = format!("{}/{}", LANGUAGE, "global_tags_across_files");
+  run_rewrite_test(&relative_path_to_tests, 2);
+  rayon::ThreadPoolBuilder::new()
+    .num_threads(1)
+    .build()
+    .unwrap()
+    .install(|| run_rewrite_test(&relative_path_to_tests, 2));
+}
+
 #[test]
 fn test_java_scenarios_user_defined_non_seed_rules() {
   initialize();
@@ -93,9 +112,73 @@ fn test_java_scenarios_consecutive_scope_level_rules() {
   );
 }
 
+// Checks that the files ignored by `.ignore` (`build/`) and the `exclude` globs (`generated/`) are not rewritten.
+#[test]
+fn test_java_scenarios_ignored_files() {
+  initialize();
+  run_rewrite_test(&format!("{}/{}", LANGUAGE, "ignored_files"), 1);
+}
+
 // run_match_test
 #[test]
 fn test_java_match_only() {
   initialize();
   run_match_test(&format!("{}/{}", LANGUAGE, "structural_find"), 20);
 }
+
+#[test]
+fn test_java_invalid_rule() {
+  initialize();
+  let path_to_test = format!("test-resources/{}/{}", LANGUAGE, "invalid_rule");
+  let args = PiranhaArguments::new(CommandLineArguments {
+    path_to_codebase: format!("{path_to_test}/input/"),
+    path_to_configurations: format!("{path_to_test}/configurations/"),
+    path_to_output_summary: None,
+    include: vec![],
+    exclude: vec![],
+  })
+  .unwrap();
+  assert!(matches!(
+    execute_piranha(&args, false),
+    Err(PiranhaError::InvalidQuery { .. })
+  ));
+}
+
+// Runs piranha over `skip_files/<scenario>/input` and checks that the only file is skipped (and its content is not reported)
+// for the expected reason.
+fn run_skip_test(scenario: &str, expected_reason: &str) {
+  let path_to_test = format!("test-resources/{}/skip_files/{}", LANGUAGE, scenario);
+  let args = PiranhaArguments::new(CommandLineArguments {
+    path_to_codebase: format!("{path_to_test}/input/"),
+    path_to_configurations: format!("{path_to_test}/configurations/"),
+    path_to_output_summary: None,
+    include: vec![],
+    exclude: vec![],
+  })
+  .unwrap();
+  let output_summaries = execute_piranha(&args, false).unwrap();
+
+  assert_eq!(output_summaries.len(), 1);
+  let summary = &output_summaries[0];
+  assert!(summary.skipped().unwrap().contains(expected_reason));
+  assert!(summary.rewrites().is_empty());
+  assert!(summary.content().is_empty());
+}
+
+#[test]
+fn test_java_skip_file_exceeding_max_file_size() {
+  initialize();
+  run_skip_test("max_file_size", "exceeds max_file_size (100 bytes)");
+}
+
+#[test]
+fn test_java_skip_file_exceeding_file_timeout() {
+  initialize();
+  run_skip_test("file_timeout", "exceeded file_timeout (0 seconds)");
+}
+
+#[test]
+fn test_java_skip_file_exceeding_global_timeout() {
+  initialize();
+  run_skip_test("global_timeout", "exceeded global_timeout (0 seconds)");
+}
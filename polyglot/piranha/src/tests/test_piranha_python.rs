@@ -15,6 +15,24 @@ use super::{initialize, run_match_test, run_rewrite_test};
 
 static LANGUAGE: &str = "python";
 
+#[test]
+fn test_python_scenarios_treated_ff1() {
+  initialize();
+  run_rewrite_test(
+    &format!("{}/{}/{}", LANGUAGE, "feature_flag_system_1", "treated"),
+    1,
+  );
+}
+
+#[test]
+fn test_python_scenarios_control_ff1() {
+  initialize();
+  run_rewrite_test(
+    &format!("{}/{}/{}", LANGUAGE, "feature_flag_system_1", "control"),
+    1,
+  );
+}
+
 #[test]
 fn test_python_delete_modify_str_literal_from_list() {
   initialize();
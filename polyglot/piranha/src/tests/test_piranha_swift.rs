@@ -35,3 +35,21 @@ fn test_cascading_delete_file_custom_global_tag() {
     3,
   );
 }
+
+#[test]
+fn test_swift_scenarios_treated_ff1() {
+  initialize();
+  run_rewrite_test(
+    &format!("{}/{}/{}", LANGUAGE, "feature_flag_system_1", "treated"),
+    2,
+  );
+}
+
+#[test]
+fn test_swift_scenarios_control_ff1() {
+  initialize();
+  run_rewrite_test(
+    &format!("{}/{}/{}", LANGUAGE, "feature_flag_system_1", "control"),
+    2,
+  );
+}
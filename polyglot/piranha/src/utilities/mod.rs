@@ -18,8 +18,13 @@ use std::fs::File;
 use std::fs::{self, DirEntry};
 use std::hash::Hash;
 use std::io::{BufReader, Read};
+use std::ops::Range;
 use std::path::PathBuf;
 
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::models::piranha_error::PiranhaError;
+
 // Reads a file.
 pub(crate) fn read_file(file_path: &PathBuf) -> Result<String, String> {
   File::open(&file_path)
@@ -31,31 +36,37 @@ pub(crate) fn read_file(file_path: &PathBuf) -> Result<String, String> {
     .map_err(|error| error.to_string())
 }
 
-// Reads a toml file. In case of error, it returns a default value (if return_default is true) else panics.
-pub(crate) fn read_toml<T>(file_path: &PathBuf, return_default: bool) -> T
+// Reads a toml file. In case the file cannot be read, it returns a default value (if return_default is true) else an error.
+// Note that a file that can be read but not deserialized always results in an error.
+pub(crate) fn read_toml<T>(file_path: &PathBuf, return_default: bool) -> Result<T, PiranhaError>
 where
   T: serde::de::DeserializeOwned + Default,
 {
-  match read_file(file_path)
-    .and_then(|content| toml::from_str::<T>(content.as_str()).map_err(|e| e.to_string()))
-  {
-    Ok(obj) => obj,
-    Err(err) => {
-      if return_default {
-        T::default()
-      } else {
-        #[rustfmt::skip]
-      panic!("Could not read file: {:?} \n Error : \n {:?}", file_path, err);
-      }
+  let content = match read_file(file_path) {
+    Ok(content) => content,
+    Err(_) if return_default => return Ok(T::default()),
+    Err(message) => {
+      return Err(PiranhaError::ReadFile {
+        path: format!("{:?}", file_path),
+        message,
+      })
     }
-  }
+  };
+  toml::from_str::<T>(content.as_str()).map_err(|e| PiranhaError::ParseToml {
+    path: format!("{:?}", file_path),
+    message: e.to_string(),
+  })
 }
 
-pub(crate) fn parse_toml<T>(content: &str) -> T
+// Deserializes the (built-in) toml content.
+pub(crate) fn parse_toml<T>(content: &str) -> Result<T, PiranhaError>
 where
   T: serde::de::DeserializeOwned + Default,
 {
-  return toml::from_str::<T>(content).unwrap();
+  toml::from_str::<T>(content).map_err(|e| PiranhaError::ParseToml {
+    path: String::from("<built-in configuration>"),
+    message: e.to_string(),
+  })
 }
 
 pub(crate) trait MapOfVec<T, V> {
@@ -71,6 +82,21 @@ impl<T: Hash + Eq, U> MapOfVec<T, U> for HashMap<T, Vec<U>> {
   }
 }
 
+/// Compiles the given glob patterns into a `GlobSet` (matching a path if any of the patterns matches it).
+pub(crate) fn get_glob_set(globs: &[String]) -> Result<GlobSet, PiranhaError> {
+  let mut builder = GlobSetBuilder::new();
+  for glob in globs {
+    builder.add(Glob::new(glob).map_err(|e| PiranhaError::InvalidGlob {
+      glob: glob.to_string(),
+      message: e.to_string(),
+    })?);
+  }
+  builder.build().map_err(|e| PiranhaError::InvalidGlob {
+    glob: globs.join(", "),
+    message: e.to_string(),
+  })
+}
+
 /// Compares two strings, ignoring whitespace
 pub(crate) fn eq_without_whitespace(s1: &str, s2: &str) -> bool {
   s1.split_whitespace()
@@ -78,6 +104,28 @@ pub(crate) fn eq_without_whitespace(s1: &str, s2: &str) -> bool {
     .eq(&s2.split_whitespace().collect::<String>())
 }
 
+/// Returns the byte range of `new_code` that differs from `old_code` (if any), i.e. the range between
+/// their longest common prefix and longest common suffix.
+/// The range is extended by a byte on either side, so that it intersects the nodes adjacent to a deletion.
+pub(crate) fn get_changed_range(old_code: &str, new_code: &str) -> Option<Range<usize>> {
+  if old_code == new_code {
+    return None;
+  }
+  let (old_code, new_code) = (old_code.as_bytes(), new_code.as_bytes());
+  let prefix = old_code
+    .iter()
+    .zip(new_code)
+    .take_while(|(a, b)| a == b)
+    .count();
+  let suffix = old_code[prefix..]
+    .iter()
+    .rev()
+    .zip(new_code[prefix..].iter().rev())
+    .take_while(|(a, b)| a == b)
+    .count();
+  Some(prefix.saturating_sub(1)..(new_code.len() - suffix + 1).min(new_code.len()))
+}
+
 /// Checks if the given `dir_entry` is a file named `file_name`
 #[cfg(test)] // Rust analyzer FP
 pub(crate) fn has_name(dir_entry: &DirEntry, file_name: &str) -> bool {
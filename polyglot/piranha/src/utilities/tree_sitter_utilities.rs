@@ -14,56 +14,112 @@ Copyright (c) 2022 Uber Technologies, Inc.
 //! Defines the traits containing with utility functions that interface with tree-sitter.
 
 use crate::{
-  models::{matches::Match, rule::Rule, rule_store::RuleStore, source_code_unit::SourceCodeUnit},
+  models::{
+    matches::Match, piranha_error::PiranhaError, rule::Rule, rule_store::RuleStore,
+    source_code_unit::SourceCodeUnit,
+  },
   utilities::MapOfVec,
 };
 use colored::Colorize;
 use itertools::Itertools;
 use log::debug;
 use std::collections::HashMap;
-#[cfg(test)]
-use tree_sitter::Parser;
-use tree_sitter::{InputEdit, Language, Node, Point, Query, QueryCapture, QueryCursor, Range};
+use tree_sitter::{
+  InputEdit, Language, Node, Parser, Point, Query, QueryCapture, QueryCursor, Range,
+};
 
 use super::eq_without_whitespace;
 
 pub(crate) trait TreeSitterHelpers {
   /// Gets the tree-sitter language model.
-  fn get_language(&self) -> Language;
+  fn get_language(&self) -> Result<Language, PiranhaError>;
+  /// Gets the extensions of the files written in the language (`self`).
+  fn get_extensions(&self) -> Vec<&str>;
   /// Compiles query string to `tree_sitter::Query`
-  fn create_query(&self, language: Language) -> Query;
+  fn create_query(&self, language: Language) -> Result<Query, PiranhaError>;
   /// Determines if the given node kind is a comment for the respective language (`self`)
   fn is_comment(&self, kind: &str) -> bool;
+  /// Determines if the indentation is part of the syntax of the respective language (`self`)
+  fn is_indentation_sensitive(&self) -> bool;
+  /// Determines if the preceding comma of a deleted last element (e.g. `b` in `[a, b]`) is always deleted
+  /// for the respective language (`self`)
+  fn deletes_preceding_comma(&self) -> bool;
 }
 
 impl TreeSitterHelpers for String {
-  fn create_query(&self, language: Language) -> Query {
-    let query = Query::new(language, self.as_str());
-    if let Ok(q) = query {
-      return q;
+  fn create_query(&self, language: Language) -> Result<Query, PiranhaError> {
+    Query::new(language, self.as_str()).map_err(|e| PiranhaError::InvalidQuery {
+      query: self.to_string(),
+      message: format!("{:?}", e),
+    })
+  }
+
+  fn get_language(&self) -> Result<Language, PiranhaError> {
+    match self.as_str() {
+      "java" => Ok(tree_sitter_java::language()),
+      "kt" => Ok(tree_sitter_kotlin::language()),
+      "py" => Ok(tree_sitter_python::language()),
+      "go" => Ok(tree_sitter_go::language()),
+      "swift" => Ok(tree_sitter_swift::language()),
+      "strings" => Ok(tree_sitter_strings::language()),
+      "ts" => Ok(tree_sitter_typescript::language_typescript()),
+      "tsx" => Ok(tree_sitter_typescript::language_tsx()),
+      "js" => Ok(tree_sitter_javascript::language()),
+      "cs" => Ok(tree_sitter_c_sharp::language()),
+      "objc" => Ok(tree_sitter_objc::language()),
+      "rb" => Ok(tree_sitter_ruby::language()),
+      "scala" => Ok(tree_sitter_scala::language()),
+      "c" => Ok(tree_sitter_c::language()),
+      "cpp" => Ok(tree_sitter_cpp::language()),
+      "rs" => Ok(tree_sitter_rust::language()),
+      "php" => Ok(tree_sitter_php::language()),
+      "dart" => Ok(tree_sitter_dart::language()),
+      "yaml" => Ok(tree_sitter_yaml::language()),
+      "json" => Ok(tree_sitter_json::language()),
+      "toml" => Ok(tree_sitter_toml::language()),
+      "xml" => Ok(tree_sitter_xml::language_xml()),
+      _ => Err(PiranhaError::UnsupportedLanguage(self.to_string())),
     }
-    panic!("Could not parse the query : {} {:?}", self, query.err());
   }
 
-  fn get_language(&self) -> Language {
+  fn get_extensions(&self) -> Vec<&str> {
     match self.as_str() {
-      "java" => tree_sitter_java::language(),
-      "kt" => tree_sitter_kotlin::language(),
-      "py" => tree_sitter_python::language(),
-      "swift" => tree_sitter_swift::language(),
-      "strings" => tree_sitter_strings::language(),
-      _ => panic!("Language not supported"),
+      "ts" => vec!["ts", "mts", "cts"],
+      "js" => vec!["js", "jsx", "mjs", "cjs"],
+      "objc" => vec!["m", "h"],
+      "rb" => vec!["rb", "rake"],
+      "scala" => vec!["scala", "sc"],
+      "c" => vec!["c", "h"],
+      "cpp" => vec!["cpp", "cc", "cxx", "hpp", "hh", "h"],
+      "rs" => vec!["rs"],
+      "php" => vec!["php", "phtml"],
+      "yaml" => vec!["yaml", "yml"],
+      // For the other languages, the language name is the file extension
+      _ => vec![self.as_str()],
     }
   }
 
   fn is_comment(&self, kind: &str) -> bool {
     match self.as_str() {
-      "java" => kind.eq("line_comment") || kind.eq("block_comment"),
+      "java" | "rs" => kind.eq("line_comment") || kind.eq("block_comment"),
       "kt" => kind.eq("comment"),
       "swift" => kind.eq("comment") || kind.eq("multiline_comment"),
+      "scala" => kind.eq("comment") || kind.eq("block_comment"),
+      "dart" => kind.eq("comment") || kind.eq("documentation_comment"),
+      "xml" => kind.eq("Comment"),
+      "go" | "ts" | "tsx" | "js" | "py" | "cs" | "objc" | "rb" | "c" | "cpp" | "php" | "yaml"
+      | "json" | "toml" => kind.eq("comment"),
       _ => false,
     }
   }
+
+  fn is_indentation_sensitive(&self) -> bool {
+    matches!(self.as_str(), "py" | "yaml")
+  }
+
+  fn deletes_preceding_comma(&self) -> bool {
+    matches!(self.as_str(), "json" | "yaml" | "toml")
+  }
 }
 
 #[rustfmt::skip]
@@ -79,16 +135,17 @@ pub(crate) trait PiranhaHelpers {
     fn get_query_capture_groups(&self, source_code: &str, query: &Query) -> HashMap<Range, Vec<Vec<QueryCapture>>> ;
 
     /// Checks if the given rule satisfies the constraint of the rule, under the substitutions obtained upon matching `rule.query`
-    fn satisfies_constraint(&self, source_code_unit: SourceCodeUnit, rule: &Rule, substitutions: &HashMap<String, String>,rule_store: &mut RuleStore,) -> bool ;
+    fn satisfies_constraint(&self, source_code_unit: &SourceCodeUnit, rule: &Rule, substitutions: &HashMap<String, String>,rule_store: &mut RuleStore,) -> Result<bool, PiranhaError> ;
     /// Applies the query upon `self`, and gets the first match
     /// # Arguments
     /// * `source_code` - the corresponding source code string for the node.
     /// * `query` - the query to be applied
     /// * `recursive` - if `true` it matches the query to `self` and `self`'s sub-ASTs, else it matches the `query` only to `self`.
+    /// * `reindent` - if `true` the (multi-line) code snippets are re-indented to the column of the replace node (for indentation sensitive languages).
     ///
     /// # Returns
     /// The range of the match in the source code and the corresponding mapping from tags to code snippets.
-    fn get_all_matches_for_query(&self, source_code: String, query: &Query, recursive: bool, replace_node_tag: Option<String>) -> Vec<Match> ;
+    fn get_all_matches_for_query(&self, source_code: &str, query: &Query, recursive: bool, replace_node_tag: Option<String>, reindent: bool) -> Vec<Match> ;
 
     /// Applies the query upon `self`, and gets all the matches
     /// # Arguments
@@ -106,29 +163,27 @@ pub(crate) trait PiranhaHelpers {
 impl PiranhaHelpers for Node<'_> {
   /// Checks if the given rule satisfies the constraint of the rule, under the substitutions obtained upon matching `rule.query`
   fn satisfies_constraint(
-    &self, source_code_unit: SourceCodeUnit, rule: &Rule, substitutions: &HashMap<String, String>,
+    &self, source_code_unit: &SourceCodeUnit, rule: &Rule, substitutions: &HashMap<String, String>,
     rule_store: &mut RuleStore,
-  ) -> bool {
+  ) -> Result<bool, PiranhaError> {
     let updated_substitutions = &substitutions
       .clone()
       .into_iter()
       .chain(rule_store.default_substitutions())
       .collect();
-    rule.constraints().iter().all(|constraint| {
-      constraint.is_satisfied(
-        *self,
-        source_code_unit.clone(),
-        rule_store,
-        updated_substitutions,
-      )
-    })
+    for constraint in rule.constraints() {
+      if !constraint.is_satisfied(*self, source_code_unit, rule_store, updated_substitutions)? {
+        return Ok(false);
+      }
+    }
+    Ok(true)
   }
 
   fn get_match_for_query(
     &self, source_code: &str, query: &Query, recursive: bool,
   ) -> Option<Match> {
     if let Some(m) = self
-      .get_all_matches_for_query(source_code.to_string(), query, recursive, None)
+      .get_all_matches_for_query(source_code, query, recursive, None, false)
       .first()
     {
       return Some(m.clone());
@@ -137,9 +192,10 @@ impl PiranhaHelpers for Node<'_> {
   }
 
   fn get_all_matches_for_query(
-    &self, source_code: String, query: &Query, recursive: bool, replace_node: Option<String>,
+    &self, source_code: &str, query: &Query, recursive: bool, replace_node: Option<String>,
+    reindent: bool,
   ) -> Vec<Match> {
-    let query_capture_groups = self.get_query_capture_groups(&source_code, query);
+    let query_capture_groups = self.get_query_capture_groups(source_code, query);
     // In the below code, we get the code snippet corresponding to each tag for each QueryMatch.
     // It could happen that we have multiple occurrences of the same tag (in queries
     // that use the quantifier operator (*/+)). Therefore for each query match, we have to group (join) the codes snippets
@@ -158,15 +214,15 @@ impl PiranhaHelpers for Node<'_> {
       // If `recursive` it allows matches to the subtree of self (Node)
       // Else it ensure that the query perfectly matches the node (`self`).
       if recursive || range_matches_self {
-        let mut replace_node_range = captured_node_range;
-
-        if let Some(replace_node_name) = &replace_node {
-          if let Some(r) = get_range_for_replace_node(query, &query_matches, replace_node_name) {
-            replace_node_range = r;
-          }
-        }
-        let code_snippet_by_tag = accumulate_repeated_tags(query, query_matches, &source_code);
-        output.push(Match::new(replace_node_range, code_snippet_by_tag));
+        output.push(get_match_for_query_matches(
+          query,
+          query_matches,
+          captured_node_range,
+          source_code,
+          &replace_node,
+          reindent,
+          query.capture_names(),
+        ));
       }
     }
     // This sorts the matches from bottom to top
@@ -201,29 +257,65 @@ impl PiranhaHelpers for Node<'_> {
   }
 }
 
+/// Gets the match corresponding to the `query_matches` (of `query`) whose outermost node is `captured_node_range`.
+/// By default the range of the match is the range of the outermost node.
+/// If `replace_node` is provided, it is the range of the node corresponding to that tag.
+/// If `reindent` is `true` the (multi-line) code snippets are re-indented to the column of the replace node.
+/// The match maps each of the `tag_names` to its code snippet.
+pub(crate) fn get_match_for_query_matches(
+  query: &Query, query_matches: Vec<Vec<QueryCapture>>, captured_node_range: Range,
+  source_code: &str, replace_node: &Option<String>, reindent: bool, tag_names: &[String],
+) -> Match {
+  let mut replace_node_range = captured_node_range;
+
+  if let Some(replace_node_name) = replace_node {
+    if let Some(r) = get_range_for_replace_node(query, &query_matches, replace_node_name) {
+      replace_node_range = r;
+    }
+  }
+  // In indentation sensitive languages (like Python) a code snippet moved to a different column
+  // has to be re-indented, otherwise its lines (except the first one) would be wrongly indented.
+  let indent_to = reindent.then_some(replace_node_range.start_point.column);
+  let code_snippet_by_tag =
+    accumulate_repeated_tags(query, query_matches, source_code, indent_to, tag_names);
+  Match::new(replace_node_range, code_snippet_by_tag)
+}
+
 // Join code snippets corresponding to the corresponding to the same tag with `\n`.
 // This scenario occurs when we use the `*` or the `+` quantifier in the tree-sitter query
 // Look at - cleanup_riles/java/rules:remove_unnecessary_nested_block
 // If tag name did not match a code snippet, add an empty string.
+// If `indent_to` is provided, the code snippets are re-indented to this column.
+// Only the `tag_names` (of the query) are accumulated.
 // Returns the mapping between the tag and source code snippet (accumulated).
 fn accumulate_repeated_tags(
   query: &Query, query_matches: Vec<Vec<tree_sitter::QueryCapture>>, source_code: &str,
+  indent_to: Option<usize>, tag_names: &[String],
 ) -> HashMap<String, String> {
   let mut code_snippet_by_tag: HashMap<String, String> = HashMap::new();
   let tag_names_by_index: HashMap<usize, &String> =
     query.capture_names().iter().enumerate().collect();
   // Iterate over each tag name in the query
-  for tag_name in query.capture_names().iter() {
+  for tag_name in tag_names {
     // Iterate over each query match for this range of code snippet
     for captures in query_matches.clone() {
       // Iterate over each capture
       for capture in captures {
         if tag_names_by_index[&(capture.index as usize)].eq(tag_name) {
-          let code_snippet = &capture.node.utf8_text(source_code.as_bytes()).unwrap();
+          let mut code_snippet = capture
+            .node
+            .utf8_text(source_code.as_bytes())
+            .unwrap()
+            .to_string();
+          let mut separator = String::from("\n");
+          if let Some(column) = indent_to {
+            code_snippet = reindent(&code_snippet, capture.node.start_position().column, column);
+            separator.push_str(&" ".repeat(column));
+          }
           code_snippet_by_tag
             .entry(tag_name.clone())
-            .and_modify(|x| x.push_str(format!("\n{}", code_snippet).as_str()))
-            .or_insert_with(|| code_snippet.to_string());
+            .and_modify(|x| x.push_str(format!("{}{}", separator, code_snippet).as_str()))
+            .or_insert(code_snippet);
         }
       }
     }
@@ -235,6 +327,25 @@ fn accumulate_repeated_tags(
   code_snippet_by_tag
 }
 
+// Re-indents the lines (except the first one) of the `code_snippet` starting at column `from`,
+// such that the `code_snippet` can be placed at column `to`.
+fn reindent(code_snippet: &str, from: usize, to: usize) -> String {
+  code_snippet
+    .split('\n')
+    .enumerate()
+    .map(|(index, line)| {
+      if index == 0 || line.trim().is_empty() {
+        line.to_string()
+      } else if from > to {
+        let indentation = line.len() - line.trim_start().len();
+        line[indentation.min(from - to)..].to_string()
+      } else {
+        format!("{}{}", " ".repeat(to - from), line)
+      }
+    })
+    .join("\n")
+}
+
 // In some queries, the `rule.query` matches a larger node, while the rewrite rule replaces the a sub-AST with a new pattern
 // For instance: cleanup_riles/java/rules:remove_unnecessary_nested_block (here the outermost tag is @block while the
 // replace_node is @nested.block)
@@ -267,7 +378,7 @@ fn get_range_for_replace_node(
 /// Returns tree-sitter's edit representation along with updated source code.
 /// Note: This method does not update `self`.
 pub(crate) fn get_tree_sitter_edit(
-  code: String, replace_range: Range, replacement: &str,
+  code: &str, replace_range: Range, replacement: &str,
 ) -> (String, InputEdit) {
   // Log the edit
   let replaced_code_snippet = &code[replace_range.start_byte..replace_range.end_byte];
@@ -287,16 +398,13 @@ pub(crate) fn get_tree_sitter_edit(
     &code[replace_range.end_byte..],
   ]
   .concat();
-  (
-    new_source_code.to_string(),
-    // Tree-sitter edit
-    _get_tree_sitter_edit(
-      replace_range,
-      replacement.as_bytes().len(),
-      code.as_bytes(),
-      new_source_code.as_bytes(),
-    ),
-  )
+  let ts_edit = _get_tree_sitter_edit(
+    replace_range,
+    replacement.as_bytes().len(),
+    code.as_bytes(),
+    new_source_code.as_bytes(),
+  );
+  (new_source_code, ts_edit)
 }
 
 // Finds the position (col and row number) for a given offset.
@@ -342,20 +450,52 @@ fn _get_tree_sitter_edit(
 ///     replacement pattern.
 ///
 /// Note that,  it escapes newline characters for tree-sitter-queries.
+/// Note that, only whole tag names are substituted (e.g. `@p` is not substituted within `@program`).
+/// A tag followed by identifier characters is delimited with braces (e.g. `@{p}rogram`).
 pub(crate) fn substitute_tags(
   input_string: String, substitutions: &HashMap<String, String>, is_tree_sitter_query: bool,
 ) -> String {
-  let mut output = input_string;
-  for (tag, substitute) in substitutions {
-    // Before replacing the key, it is transformed to a tree-sitter tag by adding `@` as prefix
-    let key = format!("@{}", tag);
-    let substitution_value = if is_tree_sitter_query {
-      substitute.replace('\n', "\\n").to_string()
-    } else {
-      substitute.to_string()
-    };
-    output = output.replace(&key, &substitution_value);
+  let mut output = String::with_capacity(input_string.len());
+  let mut rest = input_string.as_str();
+  while let Some(at_index) = rest.find('@') {
+    output.push_str(&rest[..at_index]);
+    rest = &rest[at_index + 1..];
+    // The candidate tag is the longest sequence of identifier characters (and `.`) after the `@`.
+    // It is shortened at the `.`s until it is a key of `substitutions`, such that `@literal.equals` is
+    // substituted as `@literal` followed by `.equals`.
+    let mut tag = &rest[..rest
+      .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+      .unwrap_or(rest.len())];
+    while !substitutions.contains_key(tag) && tag.contains('.') {
+      tag = &tag[..tag.rfind('.').unwrap()];
+    }
+    // The length of the tag in `input_string` (i.e. including the braces of a delimited tag)
+    let mut tag_length = tag.len();
+    if tag.is_empty() {
+      if let Some(delimited_tag) = rest
+        .strip_prefix('{')
+        .and_then(|r| r.split_once('}'))
+        .map(|(t, _)| t)
+        .filter(|t| substitutions.contains_key(*t))
+      {
+        tag = delimited_tag;
+        tag_length = tag.len() + 2;
+      }
+    }
+    match substitutions.get(tag) {
+      Some(substitute) => {
+        let substitution_value = if is_tree_sitter_query {
+          substitute.replace('\n', "\\n")
+        } else {
+          substitute.to_string()
+        };
+        output.push_str(&substitution_value);
+        rest = &rest[tag_length..];
+      }
+      None => output.push('@'),
+    }
   }
+  output.push_str(rest);
   output
 }
 
@@ -366,7 +506,7 @@ pub(crate) fn get_node_for_range(root_node: Node, start_byte: usize, end_byte: u
     .unwrap()
 }
 
-fn get_non_str_eq_parent(node: Node, source_code: String) -> Option<Node> {
+fn get_non_str_eq_parent<'a>(node: Node<'a>, source_code: &str) -> Option<Node<'a>> {
   if let Some(parent) = node.parent() {
     if !eq_without_whitespace(
       parent.utf8_text(source_code.as_bytes()).unwrap(),
@@ -382,12 +522,12 @@ fn get_non_str_eq_parent(node: Node, source_code: String) -> Option<Node> {
 
 /// Returns the node, its parent, grand parent and great grand parent
 pub(crate) fn get_context<'a>(
-  root_node: Node, prev_node: Node<'a>, source_code: String, count: u8,
+  root_node: Node, prev_node: Node<'a>, source_code: &str, count: u8,
 ) -> Vec<Node<'a>> {
   let mut output = Vec::new();
   if count > 0 {
     output.push(prev_node);
-    if let Some(parent) = get_non_str_eq_parent(prev_node, source_code.to_string()) {
+    if let Some(parent) = get_non_str_eq_parent(prev_node, source_code) {
       output.extend(get_context(root_node, parent, source_code, count - 1));
     }
   }
@@ -403,13 +543,14 @@ pub(crate) fn get_replace_range(input_edit: InputEdit) -> Range {
   }
 }
 
-#[cfg(test)]
-pub(crate) fn get_parser(language: String) -> Parser {
+/// Creates a parser for the given language.
+/// Returns `PiranhaError::UnsupportedLanguage` if the language is not supported (or its grammar is incompatible).
+pub(crate) fn get_parser(language: String) -> Result<Parser, PiranhaError> {
   let mut parser = Parser::new();
   parser
-    .set_language(language.get_language())
-    .expect("Could not set the language for the parser.");
-  parser
+    .set_language(language.get_language()?)
+    .map_err(|_| PiranhaError::UnsupportedLanguage(language))?;
+  Ok(parser)
 }
 
 #[cfg(test)]
@@ -20,9 +20,10 @@ use tree_sitter::Query;
 use crate::models::piranha_arguments::PiranhaArgumentsBuilder;
 
 use {
-  super::{get_parser, substitute_tags, PiranhaHelpers, TreeSitterHelpers},
+  super::{get_parser, reindent, substitute_tags, PiranhaHelpers, TreeSitterHelpers},
   crate::models::{
-    constraint::Constraint, rule::Rule, rule_store::RuleStore, source_code_unit::SourceCodeUnit,
+    constraint::Constraint, piranha_error::PiranhaError, rule::Rule, rule_store::RuleStore,
+    source_code_unit::SourceCodeUnit,
   },
 };
 
@@ -44,7 +45,7 @@ fn test_get_all_matches_for_query_positive() {
     "#;
   let language_name = String::from("java");
   let query = Query::new(
-    language_name.get_language(),
+    language_name.get_language().unwrap(),
     r#"((
         (method_invocation 
           name : (_) @name
@@ -62,17 +63,18 @@ fn test_get_all_matches_for_query_positive() {
   )
   .unwrap();
 
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
   let ast = parser
     .parse(&source_code, None)
     .expect("Could not parse code");
   let node = ast.root_node();
 
   let matches = node.get_all_matches_for_query(
-    source_code.to_string(),
+    source_code,
     &query,
     true,
     Some("method_invocation".to_string()),
+    false,
   );
   assert_eq!(matches.len(), 2);
 }
@@ -95,7 +97,7 @@ fn test_get_all_matches_for_query_negative() {
     "#;
   let language_name = String::from("java");
   let query = Query::new(
-    language_name.get_language(),
+    language_name.get_language().unwrap(),
     r#"((
         (method_invocation 
           name : (_) @name
@@ -113,17 +115,18 @@ fn test_get_all_matches_for_query_negative() {
   )
   .unwrap();
 
-  let mut parser = get_parser(String::from("java"));
+  let mut parser = get_parser(String::from("java")).unwrap();
   let ast = parser
     .parse(&source_code, None)
     .expect("Could not parse code");
   let node = ast.root_node();
 
   let matches = node.get_all_matches_for_query(
-    source_code.to_string(),
+    source_code,
     &query,
     true,
     Some("method_invocation".to_string()),
+    false,
   );
   assert!(matches.is_empty());
 }
@@ -166,7 +169,7 @@ fn test_satisfies_constraints_positive() {
 
   let mut rule_store = RuleStore::dummy();
   let language_name = String::from("java");
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
   let piranha_args = PiranhaArgumentsBuilder::default()
     .language_name(language_name)
     .build()
@@ -177,22 +180,25 @@ fn test_satisfies_constraints_positive() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     &piranha_args,
-  );
+  )
+  .unwrap();
 
   let node = &source_code_unit
     .root_node()
     .descendant_for_byte_range(50, 72)
     .unwrap();
 
-  assert!(node.satisfies_constraint(
-    source_code_unit.clone(),
-    &rule,
-    &HashMap::from([
-      ("variable_name".to_string(), "isFlagTreated".to_string()),
-      ("init".to_string(), "true".to_string())
-    ]),
-    &mut rule_store,
-  ));
+  assert!(node
+    .satisfies_constraint(
+      &source_code_unit,
+      &rule,
+      &HashMap::from([
+        ("variable_name".to_string(), "isFlagTreated".to_string()),
+        ("init".to_string(), "true".to_string())
+      ]),
+      &mut rule_store,
+    )
+    .unwrap());
 }
 
 #[test]
@@ -233,7 +239,7 @@ fn test_satisfies_constraints_negative() {
 
   let mut rule_store = RuleStore::dummy();
   let language_name = String::from("java");
-  let mut parser = get_parser(language_name.to_string());
+  let mut parser = get_parser(language_name.to_string()).unwrap();
   let piranha_arguments = &PiranhaArgumentsBuilder::default()
     .language_name(language_name)
     .build()
@@ -244,22 +250,25 @@ fn test_satisfies_constraints_negative() {
     &HashMap::new(),
     PathBuf::new().as_path(),
     piranha_arguments,
-  );
+  )
+  .unwrap();
 
   let node = &source_code_unit
     .root_node()
     .descendant_for_byte_range(50, 72)
     .unwrap();
 
-  assert!(!node.satisfies_constraint(
-    source_code_unit.clone(),
-    &rule,
-    &HashMap::from([
-      ("variable_name".to_string(), "isFlagTreated".to_string()),
-      ("init".to_string(), "true".to_string())
-    ]),
-    &mut rule_store,
-  ));
+  assert!(!node
+    .satisfies_constraint(
+      &source_code_unit,
+      &rule,
+      &HashMap::from([
+        ("variable_name".to_string(), "isFlagTreated".to_string()),
+        ("init".to_string(), "true".to_string())
+      ]),
+      &mut rule_store,
+    )
+    .unwrap());
 }
 
 #[test]
@@ -277,3 +286,79 @@ fn test_substitute_tags() {
     "isFlagTreated foo bar true"
   )
 }
+
+/// Checks that only whole tag names are substituted (e.g. `@p` is not substituted within `@program`),
+/// and that a tag can be followed by a `.` (e.g. `@literal.equals`) or delimited (e.g. `@{p}rogram`).
+#[test]
+fn test_substitute_tags_whole_tag_names() {
+  let substitutions = HashMap::from([
+    ("p".to_string(), "program".to_string()),
+    ("treated".to_string(), "true".to_string()),
+    ("a.lhs".to_string(), "x".to_string()),
+    ("literal".to_string(), "\"abc\"".to_string()),
+  ]);
+  let substitute = |input: &str| substitute_tags(input.to_string(), &substitutions, false);
+  assert_eq!(
+    substitute("(program) @program @p"),
+    "(program) @program program"
+  );
+  assert_eq!(
+    substitute("@treated_complement @@treated"),
+    "@treated_complement @true"
+  );
+  assert_eq!(substitute("@a.lhs @a.rhs @a."), "x @a.rhs @a.");
+  assert_eq!(substitute("@literal.equals(@p)"), "\"abc\".equals(program)");
+  assert_eq!(substitute("@{p}_name @{q}_name"), "program_name @{q}_name");
+}
+
+#[test]
+fn test_reindent() {
+  let code_snippet = "if x:\n        foo()\n\n        bar()";
+  assert_eq!(
+    reindent(code_snippet, 8, 4),
+    "if x:\n    foo()\n\n    bar()"
+  );
+  assert_eq!(
+    reindent(code_snippet, 4, 8),
+    "if x:\n            foo()\n\n            bar()"
+  );
+}
+
+#[test]
+fn test_is_indentation_sensitive() {
+  assert!("py".to_string().is_indentation_sensitive());
+  assert!("yaml".to_string().is_indentation_sensitive());
+  assert!(!"java".to_string().is_indentation_sensitive());
+}
+
+#[test]
+fn test_get_parser_unsupported_language() {
+  assert!(get_parser("java".to_string()).is_ok());
+  assert!(matches!(
+    get_parser("cobol".to_string()),
+    Err(PiranhaError::UnsupportedLanguage(language)) if language == "cobol"
+  ));
+}
+
+#[test]
+fn test_get_extensions() {
+  assert_eq!("java".to_string().get_extensions(), vec!["java"]);
+  assert_eq!("ts".to_string().get_extensions(), vec!["ts", "mts", "cts"]);
+  assert_eq!("tsx".to_string().get_extensions(), vec!["tsx"]);
+  assert_eq!(
+    "js".to_string().get_extensions(),
+    vec!["js", "jsx", "mjs", "cjs"]
+  );
+  assert_eq!("objc".to_string().get_extensions(), vec!["m", "h"]);
+  assert_eq!("rb".to_string().get_extensions(), vec!["rb", "rake"]);
+  assert_eq!("scala".to_string().get_extensions(), vec!["scala", "sc"]);
+  assert_eq!("c".to_string().get_extensions(), vec!["c", "h"]);
+  assert_eq!(
+    "cpp".to_string().get_extensions(),
+    vec!["cpp", "cc", "cxx", "hpp", "hh", "h"]
+  );
+  assert_eq!("rs".to_string().get_extensions(), vec!["rs"]);
+  assert_eq!("php".to_string().get_extensions(), vec!["php", "phtml"]);
+  assert_eq!("yaml".to_string().get_extensions(), vec!["yaml", "yml"]);
+  assert_eq!("json".to_string().get_extensions(), vec!["json"]);
+}
@@ -11,11 +11,11 @@ Copyright (c) 2022 Uber Technologies, Inc.
  limitations under the License.
 */
 
-use crate::utilities::find_file;
+use crate::{models::piranha_error::PiranhaError, utilities::find_file};
 use serde_derive::Deserialize;
 use std::path::PathBuf;
 
-use super::{read_file, read_toml};
+use super::{get_changed_range, get_glob_set, read_file, read_toml};
 
 #[derive(Deserialize, Default)]
 struct TestStruct {
@@ -37,7 +37,7 @@ fn test_read_file() {
 fn test_read_toml() {
   let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
   let path_to_test_file = project_root.join("test-resources/utility_tests/sample.toml");
-  let result: TestStruct = read_toml(&path_to_test_file, false);
+  let result: TestStruct = read_toml(&path_to_test_file, false).unwrap();
   assert!(result.name.eq("Piranha"));
 }
 
@@ -46,10 +46,19 @@ fn test_read_toml_default() {
   let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
   let path_to_test_file =
     project_root.join("test-resources/utility_tests/another_sample.toml.toml");
-  let result: TestStruct = read_toml(&path_to_test_file, true);
+  let result: TestStruct = read_toml(&path_to_test_file, true).unwrap();
   assert!(result.name.eq(""));
 }
 
+#[test]
+fn test_read_toml_negative() {
+  let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+  let path_to_test_file =
+    project_root.join("test-resources/utility_tests/another_sample.toml.toml");
+  let result: Result<TestStruct, PiranhaError> = read_toml(&path_to_test_file, false);
+  assert!(matches!(result, Err(PiranhaError::ReadFile { .. })));
+}
+
 #[test]
 fn test_find_file_positive() {
   let project_root =
@@ -66,3 +75,29 @@ fn test_find_file_negative() {
   let f = find_file(&project_root, "another_sample.toml.toml");
   assert!(f.is_file());
 }
+
+#[test]
+fn test_get_changed_range() {
+  // Update
+  assert_eq!(get_changed_range("a = foo();", "a = bar();"), Some(3..8));
+  // Insertion
+  assert_eq!(get_changed_range("a = b;", "a = b + c;"), Some(4..10));
+  // Deletion
+  assert_eq!(get_changed_range("a = b + c;", "a = b;"), Some(4..6));
+  // No change
+  assert_eq!(get_changed_range("a = b;", "a = b;"), None);
+}
+
+#[test]
+fn test_get_glob_set() {
+  let glob_set = get_glob_set(&["generated/**".to_string(), "**/*Test.java".to_string()]).unwrap();
+  assert!(glob_set.is_match("generated/Sample.java"));
+  assert!(glob_set.is_match("src/test/SampleTest.java"));
+  assert!(!glob_set.is_match("src/main/Sample.java"));
+}
+
+#[test]
+fn test_get_glob_set_invalid() {
+  let result = get_glob_set(&["src/{main".to_string()]);
+  assert!(matches!(result, Err(PiranhaError::InvalidGlob { .. })));
+}
@@ -0,0 +1,58 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+#include <stdbool.h>
+#include "flags.h"
+
+#include "old_renderer.h"
+
+
+static int legacy_buffer[16];
+
+#if defined(FEATURE_OTHER)
+#define RENDERER_VERSION 3
+
+#else
+#define RENDERER_VERSION 1
+
+#endif
+
+
+
+#ifdef FEATURE_OTHER
+static const char *other = "other";
+#endif
+
+void render(int x) {
+  render_old(x);
+}
+
+int count(int x) {
+  return 0;
+  
+}
+
+int compute(int x) {
+  
+  
+  return x;
+}
+
+int select_mode(void) {
+  return 1;
+}
+
+void log_event(void) {
+  
+  log_message("other");
+}
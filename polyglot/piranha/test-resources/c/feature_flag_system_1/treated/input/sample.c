@@ -0,0 +1,76 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+#include <stdbool.h>
+#include "flags.h"
+
+#ifdef FEATURE_STALE
+#include "new_renderer.h"
+#else
+#include "old_renderer.h"
+#endif
+
+#ifndef FEATURE_STALE
+static int legacy_buffer[16];
+#endif
+
+#if defined(FEATURE_STALE) && !defined(FEATURE_OTHER)
+#define RENDERER_VERSION 2
+#elif defined(FEATURE_OTHER)
+#define RENDERER_VERSION 3
+#else
+#define RENDERER_VERSION 1
+#endif
+
+#if FEATURE_STALE
+static const char *name = "new";
+#endif
+
+#ifdef FEATURE_OTHER
+static const char *other = "other";
+#endif
+
+void render(int x) {
+  if (is_enabled(STALE_FLAG)) {
+    render_new(x);
+  } else {
+    render_old(x);
+  }
+}
+
+int count(int x) {
+  if (is_disabled(STALE_FLAG) || x > 10) {
+    return 0;
+  }
+  return x;
+}
+
+int compute(int x) {
+  bool enabled = is_enabled(STALE_FLAG);
+  if (enabled && x > 0) {
+    return x * 2;
+  }
+  return x;
+}
+
+int select_mode(void) {
+  return !is_enabled(STALE_FLAG) ? 1 : 2;
+}
+
+void log_event(void) {
+  if (is_enabled(STALE_FLAG)) {
+    log_message("stale");
+    return;
+  }
+  log_message("other");
+}
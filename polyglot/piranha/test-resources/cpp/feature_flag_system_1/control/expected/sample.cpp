@@ -0,0 +1,48 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+#include "flags.h"
+
+#include "old_renderer.h"
+
+
+namespace renderer {
+
+class Renderer {
+ public:
+  explicit Renderer(const Flags& flags) : flags_(flags) {}
+
+  void Render(int x) {
+    RenderOld(x);
+  }
+
+  int Count(int x) const {
+    return 0;
+    
+  }
+
+  int Compute(int x) const {
+    
+    
+    return x;
+  }
+
+  int Mode() const { return 1; }
+
+void RenderLegacy();
+
+ private:
+  const Flags& flags_;
+};
+
+}  // namespace renderer
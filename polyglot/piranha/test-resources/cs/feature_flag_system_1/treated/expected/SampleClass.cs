@@ -0,0 +1,79 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+using System;
+
+namespace Experiments.Sample
+{
+    public class SampleClass
+    {
+        
+
+        public void IfStatements(bool x)
+        {
+            Console.WriteLine("treated");
+
+            if (x)
+            {
+                Console.WriteLine("control or x");
+            }
+
+            if (x)
+            {
+                Console.WriteLine("treated and x");
+            }
+        }
+
+        public string Ternary()
+        {
+            return "treated";
+        }
+
+        public void LocalVariables(bool x)
+        {
+            
+            if (x)
+            {
+                Console.WriteLine("treated and x");
+            }
+            Configure(true);
+        }
+
+        public void Field()
+        {
+            Console.WriteLine("treated");
+        }
+
+        public string EarlyReturn()
+        {
+            return "treated";
+            
+            
+        }
+
+        public void Preprocessor()
+        {
+Console.WriteLine("treated");
+#if DEBUG
+            Console.WriteLine("debug");
+
+#endif
+        }
+
+        private void Configure(bool value)
+        {
+        }
+    }
+
+    
+}
@@ -0,0 +1,104 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+using System;
+
+namespace Experiments.Sample
+{
+    public class SampleClass
+    {
+        private bool enabled = Flags.IsEnabled(FeatureFlag.StaleFlag);
+
+        public void IfStatements(bool x)
+        {
+            if (Flags.IsEnabled(FeatureFlag.StaleFlag))
+            {
+                Console.WriteLine("treated");
+            }
+            else
+            {
+                Console.WriteLine("control");
+            }
+
+            if (Flags.IsDisabled(FeatureFlag.StaleFlag) || x)
+            {
+                Console.WriteLine("control or x");
+            }
+
+            if (!Flags.IsEnabled(FeatureFlag.StaleFlag))
+            {
+                Console.WriteLine("control");
+            }
+            else if (x)
+            {
+                Console.WriteLine("treated and x");
+            }
+        }
+
+        public string Ternary()
+        {
+            return Flags.IsEnabled(FeatureFlag.StaleFlag) ? "treated" : "control";
+        }
+
+        public void LocalVariables(bool x)
+        {
+            var isTreated = Flags.IsEnabled(FeatureFlag.StaleFlag);
+            if (isTreated && x)
+            {
+                Console.WriteLine("treated and x");
+            }
+            Configure(isTreated);
+        }
+
+        public void Field()
+        {
+            if (enabled)
+            {
+                Console.WriteLine("treated");
+            }
+        }
+
+        public string EarlyReturn()
+        {
+            if (Flags.IsEnabled(FeatureFlag.StaleFlag))
+            {
+                return "treated";
+            }
+            Console.WriteLine("control");
+            return "control";
+        }
+
+        public void Preprocessor()
+        {
+#if STALE_FLAG
+            Console.WriteLine("treated");
+#else
+            Console.WriteLine("control");
+#endif
+#if DEBUG
+            Console.WriteLine("debug");
+#elif !STALE_FLAG
+            Console.WriteLine("control");
+#endif
+        }
+
+        private void Configure(bool value)
+        {
+        }
+    }
+
+    internal class StaleFlagHolder
+    {
+        private bool stale = Flags.IsEnabled(FeatureFlag.StaleFlag);
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright (c) 2022 Uber Technologies, Inc.
+//
+// <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+// except in compliance with the License. You may obtain a copy of the License at
+// <p>http://www.apache.org/licenses/LICENSE-2.0
+//
+// <p>Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+import 'package:flutter/material.dart';
+
+import 'experiments.dart';
+
+class HomePage extends StatelessWidget {
+  final Experiments experiments;
+
+  const HomePage({Key? key, required this.experiments}) : super(key: key);
+
+  @override
+  Widget build(BuildContext context) {
+    return Column(
+      children: [
+        const Header(),
+        const NewBanner(),
+        const NewBody(),
+        if (experiments.isEnabled(Flag.otherFlag)) const OtherBody(),
+        const Footer(),
+      ],
+    );
+  }
+
+  double padding() {
+    return 16.0;
+  }
+
+  bool showLegacyMenu(bool isAdmin) {
+    return isAdmin;
+  }
+
+  String title() {
+    return 'New';
+  }
+
+  bool canRender(bool visible, bool cached) {
+    return visible || cached;
+  }
+}
@@ -0,0 +1,46 @@
+package cases
+
+import (
+	"fmt"
+
+	"example.com/flags"
+)
+
+type Service struct{}
+
+func ifElse(exp flags.Experiment) {
+	fmt.Println("control")
+	fmt.Println("done")
+}
+
+func booleanExpressions(exp flags.Experiment, other bool) {
+	fmt.Println("or")
+}
+
+func shortVariable(exp flags.Experiment) {
+	fmt.Println(false)
+}
+
+func (s *Service) Handle(exp flags.Experiment) string {
+	return "off"
+}
+
+func shadowing(exp flags.Experiment) {
+	msg := "control"
+	fmt.Println(msg)
+}
+
+func switchCase(exp flags.Experiment, kind int) {
+	switch kind {
+	case 2:
+		fmt.Println("two")
+	}
+}
+
+func switchOnlyFlag(exp flags.Experiment, kind int) {
+	switch kind {
+	case 1:
+		fmt.Println("control")
+	}
+	fmt.Println("after")
+}
@@ -0,0 +1,54 @@
+package cases
+
+import (
+	"fmt"
+
+	"example.com/flags"
+)
+
+type Service struct{}
+
+func ifElse(exp flags.Experiment) {
+	fmt.Println("treated")
+	fmt.Println("done")
+}
+
+func booleanExpressions(exp flags.Experiment, other bool) {
+	if other {
+		fmt.Println("and")
+	}
+	if other {
+		fmt.Println("or")
+	}
+}
+
+func shortVariable(exp flags.Experiment) {
+	fmt.Println("enabled")
+	fmt.Println(true)
+}
+
+func (s *Service) Handle(exp flags.Experiment) string {
+	return "on"
+}
+
+func shadowing(exp flags.Experiment) {
+	msg := "control"
+	{
+		msg := "treated"
+		fmt.Println(msg)
+	}
+	fmt.Println(msg)
+}
+
+func switchCase(exp flags.Experiment, kind int) {
+	switch kind {
+	case 1:
+		fmt.Println("one")
+	case 2:
+		fmt.Println("two")
+	}
+}
+
+func switchOnlyFlag(exp flags.Experiment, kind int) {
+	fmt.Println("after")
+}
@@ -0,0 +1,73 @@
+package cases
+
+import (
+	"fmt"
+
+	"example.com/flags"
+)
+
+type Service struct{}
+
+func ifElse(exp flags.Experiment) {
+	if exp.IsToggleEnabled(flags.STALE_FLAG) {
+		fmt.Println("treated")
+	} else {
+		fmt.Println("control")
+	}
+	fmt.Println("done")
+}
+
+func booleanExpressions(exp flags.Experiment, other bool) {
+	if exp.IsToggleEnabled(flags.STALE_FLAG) && other {
+		fmt.Println("and")
+	}
+	if !exp.IsToggleEnabled(flags.STALE_FLAG) || other {
+		fmt.Println("or")
+	}
+}
+
+func shortVariable(exp flags.Experiment) {
+	enabled := exp.IsToggleEnabled(flags.STALE_FLAG)
+	if enabled {
+		fmt.Println("enabled")
+	}
+	fmt.Println(enabled)
+}
+
+func (s *Service) Handle(exp flags.Experiment) string {
+	var isOff bool = exp.IsToggleDisabled(flags.STALE_FLAG)
+	if isOff {
+		return "off"
+	}
+	return "on"
+}
+
+func shadowing(exp flags.Experiment) {
+	msg := "control"
+	if exp.IsToggleEnabled(flags.STALE_FLAG) {
+		msg := "treated"
+		fmt.Println(msg)
+	}
+	fmt.Println(msg)
+}
+
+func switchCase(exp flags.Experiment, kind int) {
+	switch kind {
+	case 1:
+		if exp.IsToggleEnabled(flags.STALE_FLAG) {
+			fmt.Println("one")
+		}
+	case 2:
+		fmt.Println("two")
+	}
+}
+
+func switchOnlyFlag(exp flags.Experiment, kind int) {
+	switch kind {
+	case 1:
+		if !exp.IsToggleEnabled(flags.STALE_FLAG) {
+			fmt.Println("control")
+		}
+	}
+	fmt.Println("after")
+}
@@ -0,0 +1,16 @@
+import * as React from "react";
+
+export function Page() {
+  return (
+    <div>
+      
+      <OldHeader />
+      <Footer />
+    </div>
+  );
+}
+
+export const Sidebar = () => {
+  
+  return <nav><Links /></nav>;
+};
@@ -0,0 +1,16 @@
+import * as React from "react";
+
+export function Page() {
+  return (
+    <div>
+      <NewBanner />
+      <NewHeader title="new" />
+      <Footer />
+    </div>
+  );
+}
+
+export const Sidebar = () => {
+  
+  return <nav></nav>;
+};
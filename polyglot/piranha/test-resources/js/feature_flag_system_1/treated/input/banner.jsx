@@ -0,0 +1,16 @@
+import * as React from "react";
+
+export function Page() {
+  return (
+    <div>
+      {isToggleEnabled(featureFlag) && <NewBanner />}
+      {isFlagTreated("featureFlag") ? <NewHeader title="new" /> : <OldHeader />}
+      <Footer />
+    </div>
+  );
+}
+
+export const Sidebar = () => {
+  const showLinks = isToggleDisabled(featureFlag);
+  return <nav>{showLinks && <Links />}</nav>;
+};
@@ -0,0 +1,45 @@
+/**
+ *    Copyright (c) 2022 Uber Technologies, Inc.
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+#import <Foundation/Foundation.h>
+
+@implementation UBEExperimentExamples
+
+- (void)ifStatements {
+  NSLog(@"treated");
+
+  if (self.enabled) {
+    NSLog(@"control or enabled");
+  }
+
+}
+
+- (NSString *)ternary {
+  return @"treated";
+}
+
+- (void)localVariables {
+  if (self.enabled) {
+    NSLog(@"treated and enabled");
+  }
+  [self configure:true];
+}
+
+- (NSString *)earlyReturn {
+  return @"treated";
+}
+
+@end
@@ -0,0 +1,71 @@
+<?php
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+function render($flags) {
+  echo "new renderer";
+}
+
+function renderWithElseIf($flags, $fast) {
+  if ($fast) {
+    echo "fast renderer";
+  } else {
+    echo "new renderer";
+  }
+}
+
+function renderWithVariable($flags) {
+  
+  echo "new renderer";
+  echo "done";
+}
+
+class Renderer {
+  private $flags;
+
+  public function bufferSize() {
+    return 64;
+  }
+
+  public function shouldRender($visible, $cached) {
+    return $visible || $cached;
+  }
+
+  public function shouldSkip($visible) {
+    return !$visible;
+  }
+
+  public function otherFlag() {
+    $limit = 10;
+    echo "$limit";
+    return $this->flags->isEnabled('other_flag');
+  }
+
+  public function legacyMode() {
+    return false;
+  }
+}
+?>
+<html>
+<body>
+<?php ?>
+  <div class="new">New layout</div>
+<?php ?>
+<?php if ($user->isAdmin()): ?>
+  <div class="admin">Admin</div>
+<?php endif; ?>
+<?php ?>
+  <p>New footer</p>
+<?php ?>
+</body>
+</html>
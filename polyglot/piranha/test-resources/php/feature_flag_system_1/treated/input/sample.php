@@ -0,0 +1,91 @@
+<?php
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+function render($flags) {
+  if ($flags->isEnabled('stale_flag')) {
+    echo "new renderer";
+  } else {
+    echo "old renderer";
+  }
+}
+
+function renderWithElseIf($flags, $fast) {
+  if ($flags->isDisabled('stale_flag')) {
+    echo "old renderer";
+  } elseif ($fast) {
+    echo "fast renderer";
+  } else {
+    echo "new renderer";
+  }
+}
+
+function renderWithVariable($flags) {
+  $enabled = $flags->isEnabled('stale_flag');
+  $retries = 3;
+  if ($enabled) {
+    echo "new renderer";
+  } else {
+    for ($i = 0; $i < $retries; $i++) {
+      echo "old renderer";
+    }
+  }
+  echo "done";
+}
+
+class Renderer {
+  private $flags;
+
+  public function bufferSize() {
+    return $this->flags->isEnabled('stale_flag') ? 64 : 16;
+  }
+
+  public function shouldRender($visible, $cached) {
+    return $visible && $this->flags->isEnabled('stale_flag') || $cached;
+  }
+
+  public function shouldSkip($visible) {
+    return !$visible or $this->flags->isDisabled('stale_flag');
+  }
+
+  public function otherFlag() {
+    $limit = 10;
+    echo "$limit";
+    return $this->flags->isEnabled('other_flag');
+  }
+
+  public function legacyMode() {
+    if ($this->flags->isDisabled('stale_flag')) {
+      return true;
+    }
+    return false;
+  }
+}
+?>
+<html>
+<body>
+<?php if ($flags->isEnabled('stale_flag')): ?>
+  <div class="new">New layout</div>
+<?php else: ?>
+  <div class="old">Old layout</div>
+<?php endif; ?>
+<?php if ($user->isAdmin()): ?>
+  <div class="admin">Admin</div>
+<?php elseif ($flags->isDisabled('stale_flag')): ?>
+  <div class="legacy">Legacy</div>
+<?php endif; ?>
+<?php if ($flags->isEnabled('stale_flag')) { ?>
+  <p>New footer</p>
+<?php } ?>
+</body>
+</html>
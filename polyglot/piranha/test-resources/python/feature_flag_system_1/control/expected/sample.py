@@ -0,0 +1,56 @@
+# Copyright (c) 2022 Uber Technologies, Inc.
+#
+# <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+# except in compliance with the License. You may obtain a copy of the License at
+# <p>http://www.apache.org/licenses/LICENSE-2.0
+#
+# <p>Unless required by applicable law or agreed to in writing, software distributed under the
+# License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+# express or implied. See the License for the specific language governing permissions and
+# limitations under the License.
+
+from experiments import Flags, is_disabled, is_enabled
+
+
+class SampleClass:
+    enabled = False
+
+    def if_statements(self, x):
+        print("control")
+
+        print("control")
+
+        print("control")
+
+        if x:
+            print("x")
+        elif True:
+            print("control")
+        else:
+            print("treated")
+
+    def empty_body(self):
+        print("control")
+
+    def boolean_expressions(self, x, y):
+        
+        
+        c = "control"
+        if x:
+            print("control")
+        return False, True, c
+
+    def local_variables(self, x):
+        
+        
+        self.configure(enabled=False)
+        return False
+
+    def early_return(self):
+        
+        print("control")
+        return "control"
+
+
+def module_function(x=False):
+    return x
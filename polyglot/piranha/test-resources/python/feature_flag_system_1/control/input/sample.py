@@ -0,0 +1,69 @@
+# Copyright (c) 2022 Uber Technologies, Inc.
+#
+# <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+# except in compliance with the License. You may obtain a copy of the License at
+# <p>http://www.apache.org/licenses/LICENSE-2.0
+#
+# <p>Unless required by applicable law or agreed to in writing, software distributed under the
+# License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+# express or implied. See the License for the specific language governing permissions and
+# limitations under the License.
+
+from experiments import Flags, is_disabled, is_enabled
+
+
+class SampleClass:
+    enabled = is_enabled(Flags.stale_flag)
+
+    def if_statements(self, x):
+        if is_enabled(Flags.stale_flag):
+            print("treated")
+            print("still treated")
+        else:
+            print("control")
+
+        if is_disabled("stale_flag"):
+            print("control")
+        else:
+            print("treated")
+            if x:
+                print("nested")
+
+        if not is_enabled(Flags.stale_flag):
+            print("control")
+
+        if x:
+            print("x")
+        elif is_disabled(Flags.stale_flag):
+            print("control")
+        else:
+            print("treated")
+
+    def empty_body(self):
+        if is_disabled(Flags.stale_flag):
+            print("control")
+
+    def boolean_expressions(self, x, y):
+        a = x and is_enabled(Flags.stale_flag)
+        b = is_disabled(Flags.stale_flag) or y
+        c = "treated" if is_enabled(Flags.stale_flag) else "control"
+        if x and is_disabled(Flags.stale_flag):
+            print("control")
+        return a, b, c
+
+    def local_variables(self, x):
+        treated = is_enabled(Flags.stale_flag)
+        if treated:
+            print("treated")
+        self.configure(enabled=treated)
+        return treated
+
+    def early_return(self):
+        if is_enabled(Flags.stale_flag):
+            return "treated"
+        print("control")
+        return "control"
+
+
+def module_function(x=is_enabled('stale_flag')):
+    return x
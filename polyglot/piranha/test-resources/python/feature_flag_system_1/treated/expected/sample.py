@@ -0,0 +1,57 @@
+# Copyright (c) 2022 Uber Technologies, Inc.
+#
+# <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+# except in compliance with the License. You may obtain a copy of the License at
+# <p>http://www.apache.org/licenses/LICENSE-2.0
+#
+# <p>Unless required by applicable law or agreed to in writing, software distributed under the
+# License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+# express or implied. See the License for the specific language governing permissions and
+# limitations under the License.
+
+from experiments import Flags, is_disabled, is_enabled
+
+
+class SampleClass:
+    enabled = True
+
+    def if_statements(self, x):
+        print("treated")
+        print("still treated")
+
+        print("treated")
+        if x:
+            print("nested")
+
+        
+
+        if x:
+            print("x")
+        
+        else:
+            print("treated")
+
+    def empty_body(self):
+        pass
+
+    def boolean_expressions(self, x, y):
+        a = x
+        b = y
+        c = "treated"
+        
+        return a, b, c
+
+    def local_variables(self, x):
+        
+        print("treated")
+        self.configure(enabled=True)
+        return True
+
+    def early_return(self):
+        return "treated"
+        
+        
+
+
+def module_function(x=True):
+    return x
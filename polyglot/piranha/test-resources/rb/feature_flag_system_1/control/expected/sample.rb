@@ -0,0 +1,67 @@
+# Copyright (c) 2022 Uber Technologies, Inc.
+#
+# <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+# except in compliance with the License. You may obtain a copy of the License at
+# <p>http://www.apache.org/licenses/LICENSE-2.0
+#
+# <p>Unless required by applicable law or agreed to in writing, software distributed under the
+# License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+# express or implied. See the License for the specific language governing permissions and
+# limitations under the License.
+
+class CheckoutController < ApplicationController
+  def show
+    render :checkout
+  end
+
+  def banner
+    show_legacy_banner
+  end
+
+  def summary
+    
+    show_legacy_summary
+  end
+
+  def discount
+    0
+  end
+
+  def eligible?
+    false
+  end
+
+  def legacy?
+    true
+  end
+
+  def label
+    "Old"
+  end
+
+  def track
+    
+    log_event("checkout")
+    
+    false
+  end
+
+  def price
+    
+    old_price
+  end
+
+  def other
+    if Flipper.enabled?(:other_flag)
+      do_something
+    end
+  end
+end
+
+module Checkout
+  def self.cleanup
+    return
+
+    
+  end
+end
@@ -0,0 +1,83 @@
+# Copyright (c) 2022 Uber Technologies, Inc.
+#
+# <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+# except in compliance with the License. You may obtain a copy of the License at
+# <p>http://www.apache.org/licenses/LICENSE-2.0
+#
+# <p>Unless required by applicable law or agreed to in writing, software distributed under the
+# License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+# express or implied. See the License for the specific language governing permissions and
+# limitations under the License.
+
+class CheckoutController < ApplicationController
+  def show
+    if Flipper.enabled?(:stale_flag)
+      render :new_checkout
+    else
+      render :checkout
+    end
+  end
+
+  def banner
+    unless Flipper.enabled?(:stale_flag)
+      show_legacy_banner
+    end
+  end
+
+  def summary
+    show_summary if Flipper.enabled?(:stale_flag, current_user)
+    show_legacy_summary unless Flipper.enabled?(:stale_flag)
+  end
+
+  def discount
+    if Flipper.disabled?(:stale_flag)
+      0
+    elsif premium?
+      10
+    else
+      5
+    end
+  end
+
+  def eligible?
+    Flipper.enabled?(:stale_flag) && current_user.present?
+  end
+
+  def legacy?
+    Flipper.disabled?(:stale_flag) or current_user.legacy?
+  end
+
+  def label
+    Flipper.enabled?(:stale_flag) ? "New" : "Old"
+  end
+
+  def track
+    enabled = Flipper.enabled?(:stale_flag)
+    log_event("checkout")
+    if enabled
+      log_event("new_checkout")
+    end
+    enabled
+  end
+
+  def price
+    unless Flipper.disabled?(:stale_flag)
+      return new_price
+    end
+    old_price
+  end
+
+  def other
+    if Flipper.enabled?(:other_flag)
+      do_something
+    end
+  end
+end
+
+module Checkout
+  def self.cleanup
+    return unless Flipper.enabled?(:stale_flag)
+
+    delete_legacy_records
+  end
+end
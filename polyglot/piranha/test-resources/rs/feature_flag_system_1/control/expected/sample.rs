@@ -0,0 +1,69 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use crate::experiments::{Experiments, Flag};
+
+pub struct Renderer {
+  experiments: Experiments,
+}
+
+impl Renderer {
+  pub fn render(&self) {
+    println!("old renderer");
+  }
+
+  pub fn render_with_else_if(&self, fast: bool) {
+    println!("old renderer");
+  }
+
+  pub fn render_with_binding(&self) {
+    let retries = 3;
+    for _ in 0..retries {
+      println!("old renderer");
+    }
+    println!("done");
+  }
+
+  pub fn render_with_if_let(&self) {}
+
+  pub fn buffer_size(&self) -> usize {
+    16
+  }
+
+  pub fn render_with_match(&self) {
+    println!("old renderer");
+    println!("rendered");
+  }
+
+  pub fn should_render(&self, visible: bool, cached: bool) -> bool {
+    cached
+  }
+
+  pub fn should_skip(&self, visible: bool) -> bool {
+    true
+  }
+
+  pub fn other_flag(&self) -> bool {
+    let limit = 10;
+    println!("{limit}");
+    self.experiments.is_enabled(Flag::OtherFlag)
+  }
+}
+
+mod legacy {
+  use crate::experiments::{Experiments, Flag};
+
+  pub fn legacy_mode(experiments: &Experiments) -> bool {
+    return true;
+  }
+}
@@ -0,0 +1,99 @@
+/*
+Copyright (c) 2022 Uber Technologies, Inc.
+
+ <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ except in compliance with the License. You may obtain a copy of the License at
+ <p>http://www.apache.org/licenses/LICENSE-2.0
+
+ <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ express or implied. See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use crate::experiments::{Experiments, Flag};
+
+pub struct Renderer {
+  experiments: Experiments,
+}
+
+impl Renderer {
+  pub fn render(&self) {
+    if self.experiments.is_enabled(Flag::StaleFlag) {
+      println!("new renderer");
+    } else {
+      println!("old renderer");
+    }
+  }
+
+  pub fn render_with_else_if(&self, fast: bool) {
+    if self.experiments.is_disabled(Flag::StaleFlag) {
+      println!("old renderer");
+    } else if fast {
+      println!("fast renderer");
+    } else {
+      println!("new renderer");
+    }
+  }
+
+  pub fn render_with_binding(&self) {
+    let enabled = self.experiments.is_enabled(Flag::StaleFlag);
+    let retries = 3;
+    if enabled {
+      println!("new renderer");
+    } else {
+      for _ in 0..retries {
+        println!("old renderer");
+      }
+    }
+    println!("done");
+  }
+
+  pub fn render_with_if_let(&self) {
+    if let true = self.experiments.is_enabled(Flag::StaleFlag) {
+      println!("new renderer");
+    }
+  }
+
+  pub fn buffer_size(&self) -> usize {
+    match self.experiments.is_enabled(Flag::StaleFlag) {
+      true => 64,
+      false => 16,
+    }
+  }
+
+  pub fn render_with_match(&self) {
+    match self.experiments.is_enabled(Flag::StaleFlag) {
+      true => {
+        println!("new renderer");
+      }
+      false => println!("old renderer"),
+    }
+    println!("rendered");
+  }
+
+  pub fn should_render(&self, visible: bool, cached: bool) -> bool {
+    visible && self.experiments.is_enabled(Flag::StaleFlag) || cached
+  }
+
+  pub fn should_skip(&self, visible: bool) -> bool {
+    !visible || self.experiments.is_disabled(Flag::StaleFlag)
+  }
+
+  pub fn other_flag(&self) -> bool {
+    let limit = 10;
+    println!("{limit}");
+    self.experiments.is_enabled(Flag::OtherFlag)
+  }
+}
+
+mod legacy {
+  use crate::experiments::{Experiments, Flag};
+
+  pub fn legacy_mode(experiments: &Experiments) -> bool {
+    if experiments.is_disabled(Flag::StaleFlag) {
+      return true;
+    }
+    false
+  }
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2022 Uber Technologies, Inc.
+//
+// <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+// except in compliance with the License. You may obtain a copy of the License at
+// <p>http://www.apache.org/licenses/LICENSE-2.0
+//
+// <p>Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+package com.uber.piranha
+
+class XPFlagCleanerPositiveCases {
+
+  private val experimentation: XPTest = new XPTest()
+
+  def conditional_contains_stale_flag(): Unit = {
+
+    println("Hi World")
+  }
+
+  def conditional_with_else_contains_stale_flag(): Unit = {
+
+    println("Hi world")
+  }
+
+  def complex_conditional_contains_stale_flag(tBool: Boolean): Unit = {
+
+    println("Hello World")
+  }
+
+  def other_api_stale_flag(): Unit = {
+
+    println("Hi world")
+  }
+
+  def assignments_containing_stale_flag(): Unit = {
+    var tBool = false
+
+    tBool = false
+
+    tBool = false
+
+    tBool = true
+
+    tBool = tBool
+
+    tBool = false
+  }
+
+  def return_contains_stale_flag(): Boolean = {
+
+    return false
+  }
+
+  def if_expression_contains_stale_flag(): String =
+    "Hi World"
+
+  def match_contains_stale_flag(): String = {
+
+    "Hi World"
+  }
+
+  def match_with_wildcard_contains_stale_flag(): Unit = {
+
+    println("Hi World")
+  }
+
+  def val_contains_stale_flag(): Unit = {
+
+    println("Hi World")
+    println(false)
+  }
+
+  def field_contains_stale_flag(): Unit = {
+  }
+
+  def misc_xp_apis_containing_stale_flag(tBool: Boolean): Unit = {
+
+    if ((tBool || true)) {
+      println("Hi World")
+    }
+  }
+
+  def return_within_if_basic(): Int = {
+
+    return 30
+  }
+
+  def return_within_if_additional(x: Int): Int = {
+    if (x == 0) {
+
+      return 75
+    }
+
+    if (x == 3) {
+      var z = 4
+
+      z = z * 5
+      return z + 10
+    }
+
+    return 100
+  }
+
+  def or_compounded_with_not(x: Int, extraToggle: Boolean): Int = {
+
+    if (extraToggle) {
+      return 0
+    } else {
+      return 1
+    }
+  }
+
+  def remove_else_if(extraToggle: Boolean): Int = {
+
+    if (extraToggle) {
+      return 0
+    } else {
+      return 1
+    }
+  }
+
+  def param_shadows_field(isStaleFlagEnabled: Boolean): Boolean = {
+
+    isStaleFlagEnabled
+  }
+}
+
+object XPFlagCleanerPositiveCases {
+
+  def apply(): XPFlagCleanerPositiveCases = new XPFlagCleanerPositiveCases()
+}
+
+class XPTest {
+  def isToggleEnabled(x: TestExperimentName): Boolean = true
+
+  def putToggleEnabled(x: TestExperimentName): Boolean = true
+
+  def includeEvent(x: TestExperimentName): Boolean = true
+
+  def isToggleDisabled(x: TestExperimentName): Boolean = true
+
+  def putToggleDisabled(x: TestExperimentName): Boolean = true
+
+  def isFlagTreated(x: TestExperimentName): Boolean = true
+}
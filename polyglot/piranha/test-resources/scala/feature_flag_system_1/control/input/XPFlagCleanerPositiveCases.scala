@@ -0,0 +1,202 @@
+// Copyright (c) 2022 Uber Technologies, Inc.
+//
+// <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+// except in compliance with the License. You may obtain a copy of the License at
+// <p>http://www.apache.org/licenses/LICENSE-2.0
+//
+// <p>Unless required by applicable law or agreed to in writing, software distributed under the
+// License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+// express or implied. See the License for the specific language governing permissions and
+// limitations under the License.
+
+package com.uber.piranha
+
+class XPFlagCleanerPositiveCases {
+
+  private val experimentation: XPTest = new XPTest()
+
+  private val isStaleFlagEnabled = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)
+
+  def conditional_contains_stale_flag(): Unit = {
+
+    if (experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) {
+      println("Hello World")
+    }
+    if (!experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) {
+      println("Hi World")
+    }
+  }
+
+  def conditional_with_else_contains_stale_flag(): Unit = {
+
+    if (experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) {
+      println("Hello World")
+    } else {
+      println("Hi world")
+    }
+  }
+
+  def complex_conditional_contains_stale_flag(tBool: Boolean): Unit = {
+
+    if (tBool || (true || experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG))) {
+      println("Hello World")
+    } else {
+      println("Hi world")
+    }
+  }
+
+  def other_api_stale_flag(): Unit = {
+
+    if (experimentation.isFlagTreated(TestExperimentName.STALE_FLAG)) {
+      println("Hello World")
+    } else {
+      println("Hi world")
+    }
+  }
+
+  def assignments_containing_stale_flag(): Unit = {
+    var tBool = false
+
+    tBool = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)
+
+    tBool = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG) && true
+
+    tBool = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG) || true
+
+    tBool = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG) || tBool
+
+    tBool = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG) && (tBool || true)
+  }
+
+  def return_contains_stale_flag(): Boolean = {
+
+    return experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)
+  }
+
+  def if_expression_contains_stale_flag(): String =
+    if (experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) "Hello World" else "Hi World"
+
+  def match_contains_stale_flag(): String = {
+
+    experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG) match {
+      case true => "Hello World"
+      case false => "Hi World"
+    }
+  }
+
+  def match_with_wildcard_contains_stale_flag(): Unit = {
+
+    experimentation.isToggleDisabled(TestExperimentName.STALE_FLAG) match {
+      case true => println("Hi World")
+      case _ => println("Hello World")
+    }
+  }
+
+  def val_contains_stale_flag(): Unit = {
+    val isEnabled = experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)
+
+    if (isEnabled) {
+      println("Hello World")
+    } else {
+      println("Hi World")
+    }
+    println(isEnabled)
+  }
+
+  def field_contains_stale_flag(): Unit = {
+
+    if (isStaleFlagEnabled) {
+      println("Hello World")
+    }
+  }
+
+  def misc_xp_apis_containing_stale_flag(tBool: Boolean): Unit = {
+
+    experimentation.putToggleEnabled(TestExperimentName.STALE_FLAG)
+
+    experimentation.includeEvent(TestExperimentName.STALE_FLAG)
+
+    experimentation.putToggleDisabled(TestExperimentName.STALE_FLAG)
+
+    if (experimentation.isToggleDisabled(TestExperimentName.STALE_FLAG) && (tBool || true)) {
+      println("Hi World")
+    }
+  }
+
+  def return_within_if_basic(): Int = {
+
+    if (experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) {
+      return 20
+    }
+    return 30
+  }
+
+  def return_within_if_additional(x: Int): Int = {
+    if (x == 0) {
+
+      if (experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) {
+        println()
+        return 0
+      }
+      return 75
+    }
+
+    if (x == 3) {
+      var z = 4
+
+      if (experimentation.isToggleEnabled(TestExperimentName.STALE_FLAG)) {
+        z += 1
+      } else {
+        z = z * 5
+        return z + 10
+      }
+      return z
+    }
+
+    return 100
+  }
+
+  def or_compounded_with_not(x: Int, extraToggle: Boolean): Int = {
+
+    if (extraToggle || !experimentation.isToggleDisabled(TestExperimentName.STALE_FLAG)) {
+      return 0
+    } else {
+      return 1
+    }
+  }
+
+  def remove_else_if(extraToggle: Boolean): Int = {
+
+    if (extraToggle) {
+      return 0
+    } else if (experimentation.isToggleDisabled(TestExperimentName.STALE_FLAG)) {
+      return 1
+    } else {
+      return 2
+    }
+  }
+
+  def param_shadows_field(isStaleFlagEnabled: Boolean): Boolean = {
+
+    isStaleFlagEnabled
+  }
+}
+
+object XPFlagCleanerPositiveCases {
+
+  def apply(): XPFlagCleanerPositiveCases = new XPFlagCleanerPositiveCases()
+}
+
+class XPTest {
+  def isToggleEnabled(x: TestExperimentName): Boolean = true
+
+  def putToggleEnabled(x: TestExperimentName): Boolean = true
+
+  def includeEvent(x: TestExperimentName): Boolean = true
+
+  def isToggleDisabled(x: TestExperimentName): Boolean = true
+
+  def putToggleDisabled(x: TestExperimentName): Boolean = true
+
+  def isFlagTreated(x: TestExperimentName): Boolean = true
+}
@@ -0,0 +1,62 @@
+/**
+ * Copyright (c) 2022 Uber Technologies, Inc.
+ *
+ * <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * <p>http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ * express or implied. See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+class SwiftExamples {
+
+    let cachedExperiments = CachedExperiments()
+
+    func test_if_statements() {
+        print("treated")
+        
+
+        print("not treated / control")
+        
+
+        print("treated")
+        
+
+        
+
+        if x {
+            print("x")
+        } else { print("treated")
+         }
+    }
+
+    func test_boolean_expressions() {
+        print("test 1")
+        
+
+        if x {
+            print("test 2")
+        }
+
+        recordMode = recordMode
+    }
+
+    func test_guard_statement() -> Bool {
+        
+        print("treated")
+        return true
+    }
+
+    func test_local_variables(enabled: Bool) {
+        
+        
+        print("treated")
+        
+        
+        configure(enabled: true)
+    }
+}
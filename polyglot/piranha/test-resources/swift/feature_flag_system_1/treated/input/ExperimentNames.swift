@@ -0,0 +1,44 @@
+/**
+ * Copyright (c) 2022 Uber Technologies, Inc.
+ *
+ * <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * <p>http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ * express or implied. See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+enum ExperimentNamesSwift: String, ExperimentKeying {
+    case test_experiment
+}
+
+enum ExperimentNamesRewards: String, ExperimentKeying {
+    case random_flag
+    case test_experiment
+}
+
+// The enclosing types are deleted once they are empty
+struct ExperimentNamesPlatform {
+    enum Experiments: String, ExperimentKeying {
+        case test_experiment
+    }
+}
+
+final class ExperimentNamesContainer {
+    struct Keys {
+        enum Experiments: String, ExperimentKeying {
+            case test_experiment
+        }
+    }
+}
+
+struct ExperimentNamesTeams {
+    enum Experiments: String, ExperimentKeying {
+        case random_flag
+        case test_experiment
+    }
+}
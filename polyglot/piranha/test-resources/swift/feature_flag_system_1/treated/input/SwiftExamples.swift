@@ -0,0 +1,79 @@
+/**
+ * Copyright (c) 2022 Uber Technologies, Inc.
+ *
+ * <p>Licensed under the Apache License, Version 2.0 (the "License"); you may not use this file
+ * except in compliance with the License. You may obtain a copy of the License at
+ *
+ * <p>http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * <p>Unless required by applicable law or agreed to in writing, software distributed under the
+ * License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either
+ * express or implied. See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+class SwiftExamples {
+
+    let cachedExperiments = CachedExperiments()
+
+    func test_if_statements() {
+        if cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment) {
+            print("treated")
+        }
+
+        if !cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment) {
+            print("not treated / control")
+        }
+
+        if (cachedExperiments.isInControlGroup(forExperiment: ExperimentNamesSwift.test_experiment)) {
+            print("not treated / control")
+        } else {
+            print("treated")
+        }
+
+        if x, cachedExperiments.isInControlGroup(forExperiment: ExperimentNamesSwift.test_experiment) {
+            print("not treated / control")
+        }
+
+        if x {
+            print("x")
+        } else if cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment) {
+            print("treated")
+        } else {
+            print("not treated / control")
+        }
+    }
+
+    func test_boolean_expressions() {
+        if cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment) || x {
+            print("test 1")
+        }
+
+        if cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment) && x {
+            print("test 2")
+        }
+
+        recordMode = cachedExperiments.isInControlGroup(forExperiment: ExperimentNamesSwift.test_experiment) ? platformUIChange : recordMode
+    }
+
+    func test_guard_statement() -> Bool {
+        guard cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment) else {
+            print("not treated / control")
+            return false
+        }
+        print("treated")
+        return true
+    }
+
+    func test_local_variables(enabled: Bool) {
+        let isTreated = cachedExperiments.isTreated(forExperiment: ExperimentNamesSwift.test_experiment)
+        var isControl: Bool = cachedExperiments.isInControlGroup(forExperiment: ExperimentNamesSwift.test_experiment)
+        if isTreated {
+            print("treated")
+        }
+        if isControl {
+            print("not treated / control")
+        }
+        configure(enabled: isTreated)
+    }
+}
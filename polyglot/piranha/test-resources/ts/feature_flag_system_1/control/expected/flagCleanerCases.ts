@@ -0,0 +1,29 @@
+import { Experiment, Flags } from "./flags";
+
+export function ifElse(exp: Experiment): void {
+  console.log("control");
+  console.log("done");
+}
+
+export function booleanExpressions(exp: Experiment, other: boolean): void {
+  console.log("or");
+}
+
+export function ternary(exp: Experiment): string {
+  return "off";
+}
+
+export class Service {
+  handle(exp: Experiment): string {
+    return "disabled";
+  }
+}
+
+export const arrow = (exp: Experiment, mode: "a" | "b"): void => {
+  console.log(true, mode);
+};
+
+export function blockScoped(exp: Experiment): void {
+  const message = "control";
+  console.log(message);
+}
@@ -0,0 +1,38 @@
+import { Experiment, Flags } from "./flags";
+
+export function ifElse(exp: Experiment): void {
+  console.log("treated");
+  console.log("done");
+}
+
+export function booleanExpressions(exp: Experiment, other: boolean): void {
+  if (other) {
+    console.log("and");
+  }
+  if (other) {
+    console.log("or");
+  }
+}
+
+export function ternary(exp: Experiment): string {
+  return "on";
+}
+
+export class Service {
+  handle(exp: Experiment): string {
+    return "enabled";
+  }
+}
+
+export const arrow = (exp: Experiment, mode: "a" | "b"): void => {
+  console.log(false, mode);
+};
+
+export function blockScoped(exp: Experiment): void {
+  const message = "control";
+  {
+    const message = "treated";
+    console.log(message);
+  }
+  console.log(message);
+}
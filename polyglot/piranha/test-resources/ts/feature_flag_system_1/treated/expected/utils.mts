@@ -0,0 +1,4 @@
+import { Experiment, Flags } from "./flags.mjs";
+
+export const isNewUi = (exp: Experiment): boolean =>
+  true;
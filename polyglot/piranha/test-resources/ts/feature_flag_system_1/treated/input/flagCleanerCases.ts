@@ -0,0 +1,47 @@
+import { Experiment, Flags } from "./flags";
+
+export function ifElse(exp: Experiment): void {
+  if (exp.isToggleEnabled(Flags.STALE_FLAG)) {
+    console.log("treated");
+  } else {
+    console.log("control");
+  }
+  console.log("done");
+}
+
+export function booleanExpressions(exp: Experiment, other: boolean): void {
+  if (exp.isToggleEnabled(Flags.STALE_FLAG) && other) {
+    console.log("and");
+  }
+  if (!exp.isToggleEnabled(Flags.STALE_FLAG) || other) {
+    console.log("or");
+  }
+}
+
+export function ternary(exp: Experiment): string {
+  return exp.isToggleDisabled(Flags.STALE_FLAG) ? "off" : "on";
+}
+
+export class Service {
+  handle(exp: Experiment): string {
+    const enabled = exp.isToggleEnabled(Flags.STALE_FLAG);
+    if (enabled) {
+      return "enabled";
+    }
+    return "disabled";
+  }
+}
+
+export const arrow = (exp: Experiment, mode: "a" | "b"): void => {
+  let isOff = exp.isToggleDisabled(Flags.STALE_FLAG);
+  console.log(isOff, mode);
+};
+
+export function blockScoped(exp: Experiment): void {
+  const message = "control";
+  if (exp.isToggleEnabled(Flags.STALE_FLAG)) {
+    const message = "treated";
+    console.log(message);
+  }
+  console.log(message);
+}
@@ -0,0 +1,4 @@
+import { Experiment, Flags } from "./flags.mjs";
+
+export const isNewUi = (exp: Experiment): boolean =>
+  exp.isToggleEnabled(Flags.STALE_FLAG) || exp.isFallback();
@@ -0,0 +1,7 @@
+import { CachedParameters } from "./cachedParameters";
+
+export class SceneStateParameters {
+  create(cachedParameters: CachedParameters): SceneStateParameters {
+    return SceneStateParametersProvider.create(cachedParameters);
+  }
+}
@@ -0,0 +1,8 @@
+import { ParameterUtils } from "./parameterUtils";
+import { CachedParameters } from "./cachedParameters";
+
+export class SceneStateParameters {
+  create(cachedParameters: CachedParameters): SceneStateParameters {
+    return ParameterUtils.create(SceneStateParameters, cachedParameters);
+  }
+}
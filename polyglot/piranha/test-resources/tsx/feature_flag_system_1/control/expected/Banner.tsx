@@ -0,0 +1,15 @@
+import * as React from "react";
+import { Experiment, Flags } from "./flags";
+
+export function Page({ exp }: { exp: Experiment }) {
+  return (
+    <div>
+      <OldHeader />
+      <Footer />
+    </div>
+  );
+}
+
+export const Sidebar = ({ exp }: { exp: Experiment }) => {
+  return <nav><Links /></nav>;
+};
@@ -0,0 +1,16 @@
+import * as React from "react";
+import { Experiment, Flags } from "./flags";
+
+export function Page({ exp }: { exp: Experiment }) {
+  return (
+    <div>
+      <NewBanner />
+      <NewHeader title="new" />
+      <Footer />
+    </div>
+  );
+}
+
+export const Sidebar = ({ exp }: { exp: Experiment }) => {
+  return <nav></nav>;
+};
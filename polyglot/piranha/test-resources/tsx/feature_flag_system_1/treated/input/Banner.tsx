@@ -0,0 +1,17 @@
+import * as React from "react";
+import { Experiment, Flags } from "./flags";
+
+export function Page({ exp }: { exp: Experiment }) {
+  return (
+    <div>
+      {exp.isToggleEnabled(Flags.STALE_FLAG) && <NewBanner />}
+      {exp.isToggleEnabled(Flags.STALE_FLAG) ? <NewHeader title="new" /> : <OldHeader />}
+      <Footer />
+    </div>
+  );
+}
+
+export const Sidebar = ({ exp }: { exp: Experiment }) => {
+  const showLinks = exp.isToggleDisabled(Flags.STALE_FLAG);
+  return <nav>{showLinks && <Links />}</nav>;
+};